@@ -0,0 +1,92 @@
+use anyhow::{anyhow, Context, Result};
+use aoc23::flag_value;
+use aoc23::runner::{header, Runner, Status, SOLUTIONS};
+use aoc23::scaffold::new_day;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How long a solution gets before it's killed, unless
+/// `--timeout` says otherwise.
+const DEFAULT_TIMEOUT_SECS: f64 = 60.0;
+
+/// Runs every solution (or just one day's, with `--day`)
+/// and prints a table of the answers in day order.
+fn run() -> Result<()> {
+    // How many solutions to run at once...
+    let workers = match flag_value("--jobs") {
+        Some(n) => n.parse::<usize>().context("Invalid --jobs")?,
+        None => std::thread::available_parallelism().map_or(1, |n| n.get()),
+    };
+
+    // How long each one gets...
+    let timeout = match flag_value("--timeout") {
+        Some(t) => t.parse::<f64>().context("Invalid --timeout")?,
+        None => DEFAULT_TIMEOUT_SECS,
+    };
+    let timeout = Duration::try_from_secs_f64(timeout).context("Invalid --timeout")?;
+
+    // Which solutions to run...
+    let day = match flag_value("--day") {
+        Some(d) => Some(d.parse::<u8>().context("Invalid --day")?),
+        None => None,
+    };
+    let solutions = SOLUTIONS
+        .iter()
+        .copied()
+        .filter(|s| day.is_none_or(|d| s.day == d))
+        .collect::<Vec<_>>();
+    if solutions.is_empty() {
+        return Err(anyhow!("No solutions to run"));
+    }
+
+    // Run them, printing each row as soon as it (and every
+    // row before it) is done. Each runs in its own process,
+    // so panics and hangs don't take down the rest...
+    let runner = Runner::from_current_exe()?.with_timeout(timeout);
+    let start = Instant::now();
+    let mut total = Duration::ZERO;
+    let mut failed = 0;
+    println!("{}", header());
+    runner.run_all(&solutions, workers, |outcome| {
+        println!("{}", outcome.row());
+        total += outcome.elapsed;
+        if outcome.status != Status::Ok {
+            failed += 1;
+        }
+    });
+    println!(
+        "Ran {} solutions ({} failed) on {} workers in {:.1?} ({:.1?} total)",
+        solutions.len(),
+        failed,
+        workers,
+        start.elapsed(),
+        total
+    );
+    Ok(())
+}
+
+/// Sets up a new day's solution (see [`new_day`]).
+fn new() -> Result<()> {
+    let day = std::env::args()
+        .nth(2)
+        .ok_or(anyhow!("Usage: aoc new <day>"))?
+        .parse::<u8>()
+        .context("Invalid day")?;
+    for path in new_day(Path::new("."), day)? {
+        println!("Created {}", path.display());
+    }
+    println!("Registered day{:02}-part1 and updated README.md", day);
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    // (Running with no command, or just flags, runs the
+    // whole year)...
+    let command = std::env::args().nth(1);
+    match command.as_deref() {
+        None | Some("run") => run(),
+        Some(c) if c.starts_with("--") => run(),
+        Some("new") => new(),
+        Some(c) => Err(anyhow!("Unknown command \"{}\" (expected run or new)", c)),
+    }
+}
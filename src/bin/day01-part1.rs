@@ -1,5 +1,7 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::input::{open_by_name, LineReader};
+use aoc23::print_answer;
+use std::io::BufRead;
 
 fn find_first_digit<I>(chars: I) -> Result<i32> 
 where
@@ -17,31 +19,32 @@ where
     Err(anyhow!("no digit found in line"))
 }
 
-fn parse_line(line: String) -> Result<i32> {
+fn parse_line(line: &str) -> Result<i32> {
     let first = find_first_digit(line.chars())?;
     let last = find_first_digit(line.chars().rev())?;
     Ok(10 * first + last)
 }
 
-fn parse_file(lines: Vec<String>) -> Result<i32> {
-    lines
-        .into_iter()
-        .map(parse_line)
-        .collect::<Result<Vec<i32>>>()?
-        .into_iter()
-        .reduce(|a, b| a + b)
-        .ok_or(anyhow!("no result found"))
+fn parse_file<R: BufRead>(lines: LineReader<R>) -> Result<i32> {
+    // (Streaming, since there's no need to hold on to
+    // the lines)...
+    let mut sum = None;
+    lines.for_each_line(|line| {
+        *sum.get_or_insert(0) += parse_line(line)?;
+        Ok(())
+    })?;
+    sum.ok_or(anyhow!("no result found"))
 }
 
 fn main() -> Result<()> {
-    // Load the input data...
-    let input_lines = load_input_lines_by_name(file!())?;
+    // Open the input data...
+    let input_lines = open_by_name(file!())?;
 
-    // Create a place to store the final count...
+    // Add up the calibration values...
     let sum = parse_file(input_lines)?;
 
     // Print out the count!
-    println!("Done. Sum = {:?}", sum);
+    print_answer(sum);
 
     // Done!
     Ok(())
@@ -70,7 +73,7 @@ mod tests {
 
     #[test]
     fn test_parse_line() -> Result<()> {
-        let res = parse_line("a1bcde2f".to_string())?;
+        let res = parse_line("a1bcde2f")?;
         assert_eq!(res, 12);
 
         Ok(())
@@ -78,16 +81,13 @@ mod tests {
 
     #[test]
     fn test_example() -> Result<()> {
-        let example_lines: Vec<String> = vec![
+        let example = [
             "1abc2",
             "pqr3stu8vwx",
             "a1b2c3d4e5f",
             "treb7uchet",
-        ]
-            .into_iter()
-            .map(|s: &str| s.to_string())
-            .collect();
-        let res = parse_file(example_lines)?;
+        ].join("\n");
+        let res = parse_file(LineReader::new(example.as_bytes()))?;
         assert_eq!(res, 142);
         Ok(())
     }
@@ -1,55 +1,27 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::{load_input_lines_by_name, print_answer};
+use aoc23::text::{english_digits, Vocabulary};
 
-const NUM_WORDS: [&'static str; 9] = [
-    "one",
-    "two",
-    "three",
-    "four",
-    "five",
-    "six",
-    "seven",
-    "eight", 
-    "nine",
-];
-
-fn reverse_string(s: &str) -> String {
-    s.chars().rev().collect::<String>()
+/// Finds the first and last digits in the line, whether
+/// they're written as numerals or words. Overlapping words
+/// count separately (eg `oneight` has a 1 and an 8).
+fn find_digits(digits: &Vocabulary<u32>, line: &str) -> Result<(i32, i32)> {
+    let (first, last) = digits
+        .first_and_last(line)
+        .ok_or(anyhow!("no digit found in line"))?;
+    Ok((i32::try_from(*first)?, i32::try_from(*last)?))
 }
 
-fn find_first_digit(line: &str, rev: bool) -> Result<i32> {
-    for (i, c) in line.chars().enumerate() {
-        match c {
-            '0'..='9' => match c.to_digit(10) {
-                Some(n) => return i32::try_from(n).map_err(|err| err.into()),
-                None => return Err(anyhow!("Couldn't convert char '{}' into digit", c)),
-            },
-            _ => {
-                for (j, w) in NUM_WORDS.iter().enumerate() {
-                    if (
-                        !rev && line[..=i].ends_with(w)
-                    ) || (
-                        rev && line[..=i].ends_with(&reverse_string(w))
-                    ) {
-                        return i32::try_from(j + 1).map_err(|err| err.into());
-                    }
-                }
-            },
-        }
-    }
-    Err(anyhow!("no digit found in line"))
-}
-
-fn parse_line(line: String) -> Result<i32> {
-    let first = find_first_digit(&line, false)?;
-    let last = find_first_digit(&reverse_string(&line), true)?;
+fn parse_line(digits: &Vocabulary<u32>, line: &str) -> Result<i32> {
+    let (first, last) = find_digits(digits, line)?;
     Ok(10 * first + last)
 }
 
 fn parse_file(lines: Vec<String>) -> Result<i32> {
+    let digits = english_digits();
     lines
-        .into_iter()
-        .map(parse_line)
+        .iter()
+        .map(|line| parse_line(&digits, line))
         .collect::<Result<Vec<i32>>>()?
         .into_iter()
         .reduce(|a, b| a + b)
@@ -64,7 +36,7 @@ fn main() -> Result<()> {
     let sum = parse_file(input_lines)?;
 
     // Print out the count!
-    println!("Done. Sum = {:?}", sum);
+    print_answer(sum);
 
     // Done!
     Ok(())
@@ -75,37 +47,29 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_find_first_digit() -> Result<()> {
-        let s = "a1two2";
-        let fd = find_first_digit(s, false)?;
-        assert_eq!(fd, 1, "Failed with string: \"{}\"", s);
-        
-        let s = "owt1a";
-        let fd = find_first_digit(s, true)?;
-        assert_eq!(fd, 2, "Failed with string: \"{}\"", s);
-        
-        let s = "9abc2";
-        let fd = find_first_digit(s, false)?;
-        assert_eq!(fd, 9, "Failed with string: \"{}\"", s);
-        
-        let s = "abonecd5";
-        let fd = find_first_digit(s, false)?;
-        assert_eq!(fd, 1, "Failed with string: \"{}\"", s);
-        
-        let s = "ab5enocd";
-        let fd = find_first_digit(s, true)?;
-        assert_eq!(fd, 5, "Failed with string: \"{}\"", s);
-        
-        let s = "abcdeight";
-        let fd = find_first_digit(s, false)?;
-        assert_eq!(fd, 8, "Failed with string: \"{}\"", s);
-       
+    fn test_find_digits() -> Result<()> {
+        let digits = english_digits();
+        let cases = [
+            ("a1two2", (1, 2)),
+            ("a1two", (1, 2)),
+            ("9abc2", (9, 2)),
+            ("abonecd5", (1, 5)),
+            ("dcone5ba", (1, 5)),
+            ("abcdeight", (8, 8)),
+            ("oneight", (1, 8)),
+            ("xtwone3four", (2, 4)),
+        ];
+        for (s, expect) in cases {
+            let res = find_digits(&digits, s)?;
+            assert_eq!(res, expect, "Failed with string: \"{}\"", s);
+        }
+        assert!(find_digits(&digits, "abc").is_err());
         Ok(())
     }
 
     #[test]
     fn test_parse_line() -> Result<()> {
-        let res = parse_line("a1bcde2f".to_string())?;
+        let res = parse_line(&english_digits(), "a1bcde2f")?;
         assert_eq!(res, 12);
 
         Ok(())
@@ -124,6 +88,21 @@ mod tests {
             .collect();
         let res = parse_file(example_lines)?;
         assert_eq!(res, 142);
+
+        let example_lines: Vec<String> = vec![
+            "two1nine",
+            "eightwothree",
+            "abcone2threexyz",
+            "xtwone3four",
+            "4nineeightseven2",
+            "zoneight234",
+            "7pqrstsixteen",
+        ]
+            .into_iter()
+            .map(|s: &str| s.to_string())
+            .collect();
+        let res = parse_file(example_lines)?;
+        assert_eq!(res, 281);
         Ok(())
     }
 }
@@ -1,40 +1,31 @@
 #![allow(dead_code)]
 
-use regex::Regex;
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::input::paragraphs;
+use aoc23::{load_input_by_name, print_answer};
+use aoc23::parse;
 
 fn parse_seeds(line: &str) -> Result<Vec<usize>> {
-    let re = Regex::new(r"seeds: ([0-9 ]+)$").unwrap();
-    let caps = re.captures(line)
-        .ok_or(anyhow!("No capture match for line \"{}\"", line))?;
-    caps
-        .get(1)
-        .ok_or(anyhow!("No captures found"))?
-        .as_str()
-        .split_whitespace()
-        .map(|s| s.parse::<usize>()
-            .map_err(|err| anyhow::Error::from(err)
-                .context(format!("Failed to parse seed \"{}\"", s))
-            )
-        )
-        .collect::<Result<Vec<_>>>()
+    let seeds = parse::line(line, |p| {
+        p.literal("seeds:")?;
+        p.many(|p| {
+            p.ws();
+            p.uint()
+        })
+    })?;
+    Ok(seeds)
 }
 
 fn parse_map_type(line: &str) -> Result<(String, String)> {
-    let re = Regex::new(r"(.*)-to-(.*) map").unwrap();
-    let caps = re.captures(line).ok_or(anyhow!("No captures found"))?;
-    let src = caps
-        .get(1)
-        .ok_or(anyhow!("No captures found"))?
-        .as_str()
-        .to_string();
-    let dest = caps
-        .get(2)
-        .ok_or(anyhow!("No captures found"))?
-        .as_str()
-        .to_string();
-    Ok((src, dest))
+    let types = parse::line(line, |p| {
+        let src = p.ident()?;
+        p.literal("-to-")?;
+        let dest = p.ident()?;
+        p.literal(" map")?;
+        p.eat(":");
+        Ok((src.to_string(), dest.to_string()))
+    })?;
+    Ok(types)
 }
 
 #[derive(Debug)]
@@ -46,19 +37,18 @@ struct MapRule {
 
 impl MapRule {
     fn parse(line: &str) -> Result<Self> {
-        let parts = line
-            .trim()
-            .split_whitespace()
-            .map(|s| s.parse::<usize>().map_err(anyhow::Error::from))
-            .collect::<Result<Vec<_>>>()?;
-        let dest_start = *parts.get(0).ok_or(anyhow!("No captures found (1)"))?;
-        let src_start = *parts.get(1).ok_or(anyhow!("No captures found (2)"))?;
-        let width = *parts.get(2).ok_or(anyhow!("No captures found (3)"))?;
-        Ok(Self {        
-            dest_start,
-            src_start,
-            width,
-        })
+        let rule = parse::line(line, |p| {
+            let mut number = || {
+                p.ws();
+                p.uint::<usize>()
+            };
+            Ok(Self {
+                dest_start: number()?,
+                src_start: number()?,
+                width: number()?,
+            })
+        })?;
+        Ok(rule)
     }
 
     fn is_in(&self, pos: usize) -> bool {
@@ -82,8 +72,8 @@ struct Mapping {
 }
 
 impl Mapping {
-    fn parse(lines: &Vec<String>) -> Result<Self> {
-        let (src, dest) = parse_map_type(&lines[0])?;
+    fn parse(lines: &[&str]) -> Result<Self> {
+        let (src, dest) = parse_map_type(lines[0])?;
         let rules = lines[1..]
             .iter()
             .map(|line| MapRule::parse(line))
@@ -107,22 +97,14 @@ impl Mapping {
 
 fn main() -> Result<()> {
     // Load and group the input lines...
-    let input_groups = load_input_lines_by_name(file!())?
-        .into_iter()
-        .fold(Vec::new(), |mut acc, line| {
-            if line.is_empty() {
-                acc.push(Vec::new());
-            } else if acc.is_empty() {
-                acc.push(vec![line]);
-            } else {
-                let last = acc.last_mut().unwrap();
-                last.push(line);
-            }
-            acc
-        });
+    let input = load_input_by_name(file!())?;
+    let input_groups = paragraphs(&input);
 
     // Parse the seeds...
-    let seeds = parse_seeds(&input_groups[0][0])?;
+    let seeds = input_groups
+        .first()
+        .ok_or(anyhow!("No seeds found"))?;
+    let seeds = parse_seeds(seeds[0])?;
 
     // Parse the mappings...
     let mappings = input_groups[1..]
@@ -140,8 +122,8 @@ fn main() -> Result<()> {
             seed
         })
         .collect::<Vec<_>>();
-    let res = seeds.iter().min();
-    println!("res: {:?}", res);
+    let res = seeds.iter().min().ok_or(anyhow!("No seeds found"))?;
+    print_answer(res);
 
     Ok(())
 }
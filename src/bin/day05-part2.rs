@@ -1,6 +1,7 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
-use regex::Regex;
+use aoc23::input::paragraphs;
+use aoc23::{load_input_by_name, print_answer};
+use aoc23::parse;
 
 struct SeedRange {
     start: usize,
@@ -14,51 +15,31 @@ impl SeedRange {
 }
 
 fn parse_seeds(line: &str) -> Result<Vec<SeedRange>> {
-    let re = Regex::new(r"seeds: ([0-9 ]+)$").unwrap();
-    let caps = re
-        .captures(line)
-        .ok_or(anyhow!("No capture match for line \"{}\"", line))?;
-    let mut res = caps
-        .get(1)
-        .ok_or(anyhow!("No captures found"))?
-        .as_str()
-        .split_whitespace()
-        .map(|s| {
-            s.parse::<usize>().map_err(|err| {
-                anyhow::Error::from(err).context(format!("Failed to parse seed \"{}\"", s))
-            })
-        })
-        .collect::<Result<Vec<_>>>()?
-        .chunks(2)
-        .enumerate()
-        .map(|(i, chunk)| {
-            let start = *chunk
-                .get(0)
-                .ok_or(anyhow!("Error getting 1st num in {}th chunk of 2", i))?;
-            let width = *chunk
-                .get(1)
-                .ok_or(anyhow!("Error getting 2nd num in {}th chunk of 2", i))?;
+    // The seeds come in (start, width) pairs...
+    let mut res = parse::line(line, |p| {
+        p.literal("seeds:")?;
+        p.many(|p| {
+            p.ws();
+            let start = p.uint()?;
+            p.ws();
+            let width = p.uint()?;
             Ok(SeedRange { start, width })
         })
-        .collect::<Result<Vec<_>>>()?;
-    res.sort_by(|a, b| a.start.cmp(&b.start));
+    })?;
+    res.sort_by_key(|a| a.start);
     Ok(res)
 }
 
 fn parse_map_type(line: &str) -> Result<(String, String)> {
-    let re = Regex::new(r"(.*)-to-(.*) map").unwrap();
-    let caps = re.captures(line).ok_or(anyhow!("No captures found"))?;
-    let src = caps
-        .get(1)
-        .ok_or(anyhow!("No captures found"))?
-        .as_str()
-        .to_string();
-    let dest = caps
-        .get(2)
-        .ok_or(anyhow!("No captures found"))?
-        .as_str()
-        .to_string();
-    Ok((src, dest))
+    let types = parse::line(line, |p| {
+        let src = p.ident()?;
+        p.literal("-to-")?;
+        let dest = p.ident()?;
+        p.literal(" map")?;
+        p.eat(":");
+        Ok((src.to_string(), dest.to_string()))
+    })?;
+    Ok(types)
 }
 
 #[derive(Debug)]
@@ -70,19 +51,18 @@ struct MapRule {
 
 impl MapRule {
     fn parse(line: &str) -> Result<Self> {
-        let parts = line
-            .trim()
-            .split_whitespace()
-            .map(|s| s.parse::<usize>().map_err(anyhow::Error::from))
-            .collect::<Result<Vec<_>>>()?;
-        let dest_start = *parts.get(0).ok_or(anyhow!("No captures found (1)"))?;
-        let src_start = *parts.get(1).ok_or(anyhow!("No captures found (2)"))?;
-        let width = *parts.get(2).ok_or(anyhow!("No captures found (3)"))?;
-        Ok(Self {
-            dest_start,
-            src_start,
-            width,
-        })
+        let rule = parse::line(line, |p| {
+            let mut number = || {
+                p.ws();
+                p.uint::<usize>()
+            };
+            Ok(Self {
+                dest_start: number()?,
+                src_start: number()?,
+                width: number()?,
+            })
+        })?;
+        Ok(rule)
     }
 
     fn was_in(&self, pos: usize) -> bool {
@@ -107,16 +87,16 @@ struct Mapping {
 }
 
 impl Mapping {
-    fn parse(lines: &Vec<String>, sort_src: bool) -> Result<Self> {
-        let (src, dest) = parse_map_type(&lines[0])?;
+    fn parse(lines: &[&str], sort_src: bool) -> Result<Self> {
+        let (src, dest) = parse_map_type(lines[0])?;
         let mut rules = lines[1..]
             .iter()
             .map(|line| MapRule::parse(line))
             .collect::<Result<Vec<_>>>()?;
         if sort_src {
-            rules.sort_by(|a, b| a.src_start.cmp(&b.src_start));
+            rules.sort_by_key(|a| a.src_start);
         } else {
-            rules.sort_by(|a, b| a.dest_start.cmp(&b.dest_start));
+            rules.sort_by_key(|a| a.dest_start);
         }
         Ok(Self { src, dest, rules })
     }
@@ -133,23 +113,14 @@ impl Mapping {
 
 fn main() -> Result<()> {
     // Load and group the input lines...
-    let input_groups =
-        load_input_lines_by_name(file!())?
-            .into_iter()
-            .fold(Vec::new(), |mut acc, line| {
-                if line.is_empty() {
-                    acc.push(Vec::new());
-                } else if acc.is_empty() {
-                    acc.push(vec![line]);
-                } else {
-                    let last = acc.last_mut().unwrap();
-                    last.push(line);
-                }
-                acc
-            });
+    let input = load_input_by_name(file!())?;
+    let input_groups = paragraphs(&input);
 
     // Parse the seeds...
-    let seeds = parse_seeds(&input_groups[0][0])?;
+    let seeds = input_groups
+        .first()
+        .ok_or(anyhow!("No seeds found"))?;
+    let seeds = parse_seeds(seeds[0])?;
 
     // Parse the mappings...
     let mappings = input_groups[1..]
@@ -194,11 +165,10 @@ fn main() -> Result<()> {
 
         // Check if it's in the initial seeds ranges...
         if seeds.iter().any(|seed| seed.contains(out)) {
-            println!("Found a match: {}", i);
+            print_answer(i);
             return Ok(());
         }
     }
-    
-    println!("Oh no! Got to the end without finding a match!");
-    Ok(())
+
+    Err(anyhow!("Got to the end without finding a match"))
 }
@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::input::ints;
+use aoc23::{load_input_lines_by_name, print_answer};
 
 #[derive(Debug)]
 struct RaceInfo {
@@ -7,34 +8,17 @@ struct RaceInfo {
     record: usize,
 }
 
-fn parse_input(lines: &Vec<String>) -> Result<Vec<RaceInfo>> {
-    let times = lines
-        .get(0)
-        .ok_or(anyhow!("Input didn't have a first line"))?
-        .split(":")
-        .collect::<Vec<_>>()
-        .get(1)
-        .ok_or(anyhow!("Couldn't get the 2nd part of the first line"))?
-        .trim()
-        .split_whitespace()
-        .map(|s| s.parse::<usize>().map_err(anyhow::Error::from))
-        .collect::<Result<Vec<_>>>()?;
-    let records = lines
-        .get(1)
-        .ok_or(anyhow!("Input didn't have a first line"))?
-        .split(":")
-        .collect::<Vec<_>>()
-        .get(1)
-        .ok_or(anyhow!("Couldn't get the 2nd part of the first line"))?
-        .trim()
-        .split_whitespace()
-        .map(|s| s.parse::<usize>().map_err(anyhow::Error::from))
-        .collect::<Result<Vec<_>>>()?;
-    times
+fn parse_input(lines: &[String]) -> Result<Vec<RaceInfo>> {
+    let times = ints::<usize>(lines.first().ok_or(anyhow!("Input didn't have a first line"))?)?;
+    let records = ints::<usize>(lines.get(1).ok_or(anyhow!("Input didn't have a second line"))?)?;
+    if times.len() != records.len() {
+        return Err(anyhow!("Got {} times but {} records", times.len(), records.len()));
+    }
+    Ok(times
         .into_iter()
-        .zip(records.into_iter())
-        .map(|(time, record)| Ok(RaceInfo { time, record }))
-        .collect::<Result<Vec<_>>>()
+        .zip(records)
+        .map(|(time, record)| RaceInfo { time, record })
+        .collect())
 }
 
 fn get_dist(charge_time: usize, total_time: usize) -> usize {
@@ -85,7 +69,7 @@ fn main() -> Result<()> {
 
     // Multiply all the win margins together...
     let res = wms.iter().fold(1, |acc, wm| acc * wm);
-    println!("Result: {}", res);
+    print_answer(res);
 
     Ok(())
 }
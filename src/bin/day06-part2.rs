@@ -1,5 +1,7 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::error::ParseError;
+use aoc23::input::int_strs;
+use aoc23::{load_input_lines_by_name, print_answer};
 
 #[derive(Debug)]
 struct RaceInfo {
@@ -7,33 +9,18 @@ struct RaceInfo {
     record: usize,
 }
 
-fn parse_input(lines: &Vec<String>) -> Result<RaceInfo> {
-    let time = lines
-        .get(0)
-        .ok_or(anyhow!("Input didn't have a first line"))?
-        .split(":")
-        .collect::<Vec<_>>()
-        .get(1)
-        .ok_or(anyhow!("Couldn't get the 2nd part of the first line"))?
-        .trim()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join("")
+/// Parses a line of numbers as one number, ignoring the
+/// spaces between them (bad kerning!).
+fn parse_kerned(line: &str) -> Result<usize> {
+    let digits = int_strs(line).collect::<String>();
+    digits
         .parse::<usize>()
-        .map_err(anyhow::Error::from)?;
-    let record = lines
-        .get(1)
-        .ok_or(anyhow!("Input didn't have a second line"))?
-        .split(":")
-        .collect::<Vec<_>>()
-        .get(1)
-        .ok_or(anyhow!("Couldn't get the 2nd part of the second line"))?
-        .trim()
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join("")
-        .parse::<usize>()
-        .map_err(anyhow::Error::from)?;
+        .map_err(|_| ParseError::new(line, "expected a number").into())
+}
+
+fn parse_input(lines: &[String]) -> Result<RaceInfo> {
+    let time = parse_kerned(lines.first().ok_or(anyhow!("Input didn't have a first line"))?)?;
+    let record = parse_kerned(lines.get(1).ok_or(anyhow!("Input didn't have a second line"))?)?;
     Ok(RaceInfo { time, record })
 }
 
@@ -81,7 +68,7 @@ fn main() -> Result<()> {
     let wm = get_win_margin(&ri)?;
 
     // Multiply all the win margins together...
-    println!("Result: {}", wm);
+    print_answer(wm);
 
     Ok(())
 }
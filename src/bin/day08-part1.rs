@@ -1,27 +1,67 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
-use regex::Regex;
-use std::collections::HashMap;
+use aoc23::dot::DOT_FLAG;
+use aoc23::graph::Graph;
+use aoc23::{has_flag, load_input_lines_by_name, print_answer};
+use aoc23::parse;
 
+/// Parses a node line like `AAA = (BBB, CCC)`.
 fn parse_line(line: &str) -> Result<(String, String, String)> {
-    let re = Regex::new(r"([A-Z]+) = \(([A-Z]+), ([A-Z]+)\)").unwrap();
-    let caps = re.captures(line).ok_or(anyhow!("Invalid line: {}", line))?;
-    let id = caps
-        .get(1)
-        .ok_or(anyhow!("Regex didn't match against line \"{}\"", line))?
-        .as_str()
-        .to_string();
-    let left = caps
-        .get(2)
-        .ok_or(anyhow!("Regex didn't match against line \"{}\"", line))?
-        .as_str()
-        .to_string();
-    let right = caps
-        .get(3)
-        .ok_or(anyhow!("Regex didn't match against line \"{}\"", line))?
-        .as_str()
-        .to_string();
-    Ok((id, left, right))
+    let node = parse::line(line, |p| {
+        let id = p.ident()?;
+        p.literal(" = (")?;
+        let left = p.ident()?;
+        p.literal(", ")?;
+        let right = p.ident()?;
+        p.literal(")")?;
+        Ok((id.to_string(), left.to_string(), right.to_string()))
+    })?;
+    Ok(node)
+}
+
+/// Builds the node network as a directed graph where
+/// each node's neighbors are `[left, right]`.
+fn build_network(lines: &[String]) -> Result<Graph> {
+    let mut network = Graph::new_directed();
+    for line in lines {
+        let (id, left, right) = parse_line(line)?;
+        network.add_labeled_edge(&id, &left, "L");
+        network.add_labeled_edge(&id, &right, "R");
+    }
+
+    // Make sure every node we can reach was defined...
+    for id in network.nodes() {
+        if network.neighbors(id).len() != 2 {
+            return Err(anyhow!("Node {} isn't defined", network.label(id)));
+        }
+    }
+    Ok(network)
+}
+
+/// Returns the node reached by going in `direction` from `node`.
+fn step(network: &Graph, node: u32, direction: char) -> Result<u32> {
+    let next = network.neighbors(node);
+    match direction {
+        'L' => Ok(next[0]),
+        'R' => Ok(next[1]),
+        _ => Err(anyhow!("Invalid direction {}", direction)),
+    }
+}
+
+/// Walks from `AAA` to `ZZZ`, returning every node visited.
+fn walk(network: &Graph, directions: &[char]) -> Result<Vec<u32>> {
+    if directions.is_empty() {
+        return Err(anyhow!("No directions"));
+    }
+    let start = network.id("AAA").ok_or(anyhow!("No node AAA"))?;
+    let end = network.id("ZZZ").ok_or(anyhow!("No node ZZZ"))?;
+    let mut path = vec![start];
+    let mut node = start;
+    while node != end {
+        let direction = directions[(path.len() - 1) % directions.len()];
+        node = step(network, node, direction)?;
+        path.push(node);
+    }
+    Ok(path)
 }
 
 fn main() -> Result<()> {
@@ -44,39 +84,63 @@ fn main() -> Result<()> {
     //     "BBB = (AAA, ZZZ)".to_string(),
     //     "ZZZ = (ZZZ, ZZZ)".to_string(),
     // ];
-    let directions = lines.get(0).ok_or(anyhow!("No input"))?;
-    let nodes = lines[2..]
-        .into_iter()
-        .map(|l| parse_line(l))
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
-        .fold(
-            HashMap::<String, (String, String)>::new(),
-            |mut acc, (id, left, right)| {
-                acc.insert(id, (left, right));
-                acc
-            },
-        );
+    let directions = lines.first().ok_or(anyhow!("No input"))?;
+    let network = build_network(&lines[2..])?;
+    let directions = directions.chars().collect::<Vec<_>>();
+
+    let path = walk(&network, &directions)?;
 
-    let mut node = "AAA".to_string();
-    let mut i: usize = 0;
-    let mut count: usize = 0;
-    while node != "ZZZ" {
-        let (left, right) = nodes.get(&node).ok_or(anyhow!("No node {}", node))?;
-        let direction = directions
-            .chars()
-            .nth(i)
-            .ok_or(anyhow!("No direction at {}", count))?;
-        node = match direction {
-            'L' => left.to_string(),
-            'R' => right.to_string(),
-            _ => return Err(anyhow!("Invalid direction {}", direction)),
-        };
-        count += 1;
-        i = if i == directions.len() - 1 { 0 } else { i + 1 };
+    // Optionally write out the network with the path
+    // highlighted...
+    if has_flag(DOT_FLAG) {
+        let path = path.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        network.dot().highlight_path(&path).write_if_requested(file!())?;
     }
 
-    println!("count: {}", count);
+    print_answer(path.len() - 1);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() -> Result<()> {
+        let lines = vec![
+            "AAA = (BBB, CCC)".to_string(),
+            "BBB = (DDD, EEE)".to_string(),
+            "CCC = (ZZZ, GGG)".to_string(),
+            "DDD = (DDD, DDD)".to_string(),
+            "EEE = (EEE, EEE)".to_string(),
+            "GGG = (GGG, GGG)".to_string(),
+            "ZZZ = (ZZZ, ZZZ)".to_string(),
+        ];
+        let network = build_network(&lines)?;
+        assert_eq!(walk(&network, &['R', 'L'])?.len(), 3);
+
+        let lines = vec![
+            "AAA = (BBB, BBB)".to_string(),
+            "BBB = (AAA, ZZZ)".to_string(),
+            "ZZZ = (ZZZ, ZZZ)".to_string(),
+        ];
+        let network = build_network(&lines)?;
+        assert_eq!(walk(&network, &['L', 'L', 'R'])?.len(), 7);
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_directions() -> Result<()> {
+        let lines = vec!["AAA = (ZZZ, ZZZ)".to_string(), "ZZZ = (ZZZ, ZZZ)".to_string()];
+        let network = build_network(&lines)?;
+        assert!(walk(&network, &[]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_undefined_node() {
+        let lines = vec!["AAA = (BBB, ZZZ)".to_string()];
+        assert!(build_network(&lines).is_err());
+    }
+}
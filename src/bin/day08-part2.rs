@@ -1,46 +1,207 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
-use regex::Regex;
+use aoc23::graph::Graph;
+use aoc23::{load_input_lines_by_name, print_answer};
+use aoc23::parse;
+use aoc23::math::{crt, lcm};
 use std::collections::HashMap;
 
+/// Parses a node line like `AAA = (BBB, CCC)`.
 fn parse_line(line: &str) -> Result<(String, String, String)> {
-    let re = Regex::new(r"([A-Za-z0-9]+) = \(([A-Za-z0-9]+), ([A-Za-z0-9]+)\)").unwrap();
-    let caps = re.captures(line).ok_or(anyhow!("Invalid line: {}", line))?;
-    let id = caps
-        .get(1)
-        .ok_or(anyhow!("Regex didn't match against line \"{}\"", line))?
-        .as_str()
-        .to_string();
-    let left = caps
-        .get(2)
-        .ok_or(anyhow!("Regex didn't match against line \"{}\"", line))?
-        .as_str()
-        .to_string();
-    let right = caps
-        .get(3)
-        .ok_or(anyhow!("Regex didn't match against line \"{}\"", line))?
-        .as_str()
-        .to_string();
-    Ok((id, left, right))
+    let node = parse::line(line, |p| {
+        let id = p.ident()?;
+        p.literal(" = (")?;
+        let left = p.ident()?;
+        p.literal(", ")?;
+        let right = p.ident()?;
+        p.literal(")")?;
+        Ok((id.to_string(), left.to_string(), right.to_string()))
+    })?;
+    Ok(node)
 }
 
-fn gcd(a: usize, b: usize) -> usize {
-    let mut a = a;
-    let mut b = b;
-    while b != 0 {
-        let t = b;
-        b = a % b;
-        a = t;
+/// Builds the node network as a directed graph where
+/// each node's neighbors are `[left, right]`.
+fn build_network(lines: &[String]) -> Result<Graph> {
+    let mut network = Graph::new_directed();
+    for line in lines {
+        let (id, left, right) = parse_line(line)?;
+        network.add_labeled_edge(&id, &left, "L");
+        network.add_labeled_edge(&id, &right, "R");
     }
-    a
+
+    // Make sure every node we can reach was defined...
+    for id in network.nodes() {
+        if network.neighbors(id).len() != 2 {
+            return Err(anyhow!("Node {} isn't defined", network.label(id)));
+        }
+    }
+    Ok(network)
 }
 
-fn lcm(dists: Vec<usize>) -> usize {
-    let mut lcm = dists[0];
-    for i in 1..dists.len() {
-        lcm = (lcm * dists[i]) / gcd(lcm, dists[i]);
+/// Returns the node reached by going in `direction` from `node`.
+fn step(network: &Graph, node: u32, direction: char) -> Result<u32> {
+    let next = network.neighbors(node);
+    match direction {
+        'L' => Ok(next[0]),
+        'R' => Ok(next[1]),
+        _ => Err(anyhow!("Invalid direction {}", direction)),
     }
-    lcm
+}
+
+/// Checks if `node` is an end node (its name ends with "Z").
+fn is_end(network: &Graph, node: u32) -> bool {
+    network.label(node).ends_with('Z')
+}
+
+/// Where a single ghost lands on end nodes.
+///
+/// The ghost's state is its node plus its position in the
+/// directions, so it has to repeat a state eventually and
+/// then loop forever. Step counts are from the start node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Ghost {
+    start: u32,
+
+    /// The step where the loop starts.
+    cycle_start: usize,
+    cycle_len: usize,
+
+    /// Steps that land on an end node before the loop starts.
+    pre_hits: Vec<usize>,
+
+    /// Steps that land on an end node during the first
+    /// time around the loop.
+    cycle_hits: Vec<usize>,
+}
+
+impl Ghost {
+    fn analyze(network: &Graph, directions: &[char], start: u32) -> Result<Self> {
+        let n = directions.len();
+        if n == 0 {
+            return Err(anyhow!("No directions"));
+        }
+        let mut seen = HashMap::new();
+        let mut hits = Vec::new();
+        let mut node = start;
+        let mut t = 0;
+        loop {
+            // Have we been here before (at the same point in
+            // the directions)?
+            if let Some(&first) = seen.get(&(node, t % n)) {
+                let (pre_hits, cycle_hits) = hits.into_iter().partition(|&h| h < first);
+                return Ok(Self {
+                    start,
+                    cycle_start: first,
+                    cycle_len: t - first,
+                    pre_hits,
+                    cycle_hits,
+                });
+            }
+            seen.insert((node, t % n), t);
+            if is_end(network, node) {
+                hits.push(t);
+            }
+            node = step(network, node, directions[t % n])?;
+            t += 1;
+        }
+    }
+
+    /// Checks if the ghost is on an end node after `t` steps.
+    fn hits_at(&self, t: usize) -> bool {
+        if t < self.cycle_start {
+            self.pre_hits.contains(&t)
+        } else {
+            let t = self.cycle_start + (t - self.cycle_start) % self.cycle_len;
+            self.cycle_hits.contains(&t)
+        }
+    }
+
+    /// Checks if the ghost lands on an end node exactly at
+    /// the multiples of its loop length, which is what
+    /// taking the LCM assumes.
+    fn is_simple(&self) -> bool {
+        self.pre_hits.is_empty() && self.cycle_hits == [self.cycle_len]
+    }
+}
+
+/// Finds the first step where every ghost is on an end node.
+fn synchronize(ghosts: &[Ghost]) -> Result<Option<usize>> {
+    // The easy case...
+    if ghosts.iter().all(|g| g.is_simple()) {
+        let lens = ghosts
+            .iter()
+            .map(|g| g.cycle_len as u64)
+            .collect::<Vec<_>>();
+        let res = lcm(&lens).ok_or(anyhow!("LCM overflowed"))?;
+        return Ok(Some(res as usize));
+    }
+
+    // Until the slowest ghost reaches its loop, it can only
+    // be on an end node at one of its early hits...
+    let settle = ghosts.iter().map(|g| g.cycle_start).max().unwrap_or(0);
+    if let Some(slowest) = ghosts.iter().find(|g| g.cycle_start == settle) {
+        let early = slowest
+            .pre_hits
+            .iter()
+            .find(|&&t| ghosts.iter().all(|g| g.hits_at(t)));
+        if let Some(&t) = early {
+            return Ok(Some(t));
+        }
+    }
+
+    // After that, every ghost is looping, so try each way of
+    // picking one loop hit per ghost and solve for when they
+    // line up...
+    if ghosts.iter().any(|g| g.cycle_hits.is_empty()) {
+        return Ok(None);
+    }
+    let mut best: Option<usize> = None;
+    let mut picks = vec![0; ghosts.len()];
+    loop {
+        let congruences = ghosts
+            .iter()
+            .zip(&picks)
+            .map(|(g, &i)| (g.cycle_hits[i] as u64, g.cycle_len as u64))
+            .collect::<Vec<_>>();
+        if let Some((t, period)) = crt(&congruences) {
+            // Move the solution up past the point where
+            // everyone is looping...
+            let (t, period) = (t as usize, period as usize);
+            let t = if t >= settle {
+                t
+            } else {
+                t + (settle - t).div_ceil(period) * period
+            };
+            best = Some(best.map_or(t, |b| b.min(t)));
+        }
+
+        // Move on to the next set of picks...
+        let mut k = 0;
+        while k < picks.len() {
+            picks[k] += 1;
+            if picks[k] < ghosts[k].cycle_hits.len() {
+                break;
+            }
+            picks[k] = 0;
+            k += 1;
+        }
+        if k == picks.len() {
+            break;
+        }
+    }
+    Ok(best)
+}
+
+/// Formats `n` with thousands separators.
+fn format_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut parts = digits
+        .as_bytes()
+        .rchunks(3)
+        .map(|c| String::from_utf8_lossy(c).to_string())
+        .collect::<Vec<_>>();
+    parts.reverse();
+    parts.join(",")
 }
 
 fn main() -> Result<()> {
@@ -58,81 +219,145 @@ fn main() -> Result<()> {
     //     "22Z = (22B, 22B)".to_string(),
     //     "XXX = (XXX, XXX)".to_string(),
     // ];
-    let directions = lines.get(0).ok_or(anyhow!("No input"))?;
-    let nodes = lines[2..]
-        .into_iter()
-        .map(|l| parse_line(l))
-        .collect::<Result<Vec<_>>>()?
-        .into_iter()
-        .fold(
-            HashMap::<String, (String, String)>::new(),
-            |mut acc, (id, left, right)| {
-                acc.insert(id, (left, right));
-                acc
-            },
-        );
+    let directions = lines.first().ok_or(anyhow!("No input"))?;
+    let network = build_network(&lines[2..])?;
+    let directions = directions.chars().collect::<Vec<_>>();
 
     // Find the starting points (nodes that end
     // with an "A")...
-    let starting_points: Vec<String> = nodes
-        .iter()
-        .filter(|(id, _)| id.ends_with("A"))
-        .map(|(id, _)| id.clone())
+    let starting_points = network
+        .nodes()
+        .filter(|&id| network.label(id).ends_with('A'))
         .collect::<Vec<_>>();
+    if starting_points.is_empty() {
+        return Err(anyhow!("No starting points found"));
+    }
     println!("Found {} starting points", starting_points.len());
 
-    // For each of those starting points, find out how
-    // many steps it takes to get to the end (Z)...
-    let dists = starting_points
+    // Work out where each ghost loops and where it hits
+    // the end nodes...
+    let ghosts = starting_points
         .iter()
-        .map(|start| {
-            // Create a running node...
-            let mut node = start.clone();
-
-            // Still define the shared state variables...
-            let mut i: usize = 0;
-            let mut count: usize = 0;
-
-            // Now, start the loop for all tracks simultaneously.
-            // End the loop when all tracks end with a "Z"
-            // (simultaneously).
-            while !node.ends_with('Z') {
-                // Get the next direction...
-                let direction = directions
-                    .chars()
-                    .nth(i)
-                    .ok_or(anyhow!("No direction at {}", count))
-                    .unwrap();
-
-                // Move the nodes in each track...
-                let (left, right) = nodes.get(&node).ok_or(anyhow!("No node {}", node)).unwrap();
-                node = match direction {
-                    'L' => left.clone(),
-                    'R' => right.clone(),
-                    _ => panic!("Invalid direction {}", direction),
-                };
-
-                // Increment the counters...
-                count += 1;
-                i = if i == directions.len() - 1 { 0 } else { i + 1 };
-            }
-            count
-        })
-        .collect::<Vec<_>>();
-    println!("Distances calculated.");
-
-    // Find the LCM of all the distances...
-    let dist_lcm = lcm(dists);
-    let dist_fmt = {
-        let mut n = dist_lcm;
-        let mut parts = Vec::new();
-        while n > 0 {
-            parts.push(format!("{:03}", n % 1000));
-            n /= 1000;
-        }
-        parts.reverse();
-        parts.join(",").trim_start_matches('0').to_string()
-    };
-    println!("LCM = {}", dist_fmt);
+        .map(|&start| Ghost::analyze(&network, &directions, start))
+        .collect::<Result<Vec<_>>>()?;
+    for g in &ghosts {
+        println!(
+            "{}: loop of {} steps from step {}, ends at {:?} then {:?}",
+            network.label(g.start),
+            g.cycle_len,
+            g.cycle_start,
+            g.pre_hits,
+            g.cycle_hits,
+        );
+    }
+    if ghosts.iter().all(|g| g.is_simple()) {
+        println!("Every ghost ends exactly once per loop, so the LCM works.");
+    } else {
+        println!("The LCM doesn't work here, so solving with CRT.");
+    }
+
+    // Find when they line up...
+    let res = synchronize(&ghosts)?.ok_or(anyhow!("The ghosts never all reach the end"))?;
+    println!("That's {} steps", format_thousands(res));
+    print_answer(res);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(lines: &[&str]) -> Result<Option<usize>> {
+        let lines = lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        let directions = lines[0].chars().collect::<Vec<_>>();
+        let network = build_network(&lines[2..])?;
+        let ghosts = network
+            .nodes()
+            .filter(|&id| network.label(id).ends_with('A'))
+            .map(|start| Ghost::analyze(&network, &directions, start))
+            .collect::<Result<Vec<_>>>()?;
+        synchronize(&ghosts)
+    }
+
+    #[test]
+    fn test_example() -> Result<()> {
+        let lines = [
+            "LR",
+            "",
+            "11A = (11B, XXX)",
+            "11B = (XXX, 11Z)",
+            "11Z = (11B, XXX)",
+            "22A = (22B, XXX)",
+            "22B = (22C, 22C)",
+            "22C = (22Z, 22Z)",
+            "22Z = (22B, 22B)",
+            "XXX = (XXX, XXX)",
+        ];
+        assert_eq!(solve(&lines)?, Some(6));
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze() -> Result<()> {
+        let lines = [
+            "L",
+            "",
+            "22A = (22Z, 22A)",
+            "22Z = (22B, 22B)",
+            "22B = (22C, 22C)",
+            "22C = (22Z, 22Z)",
+        ];
+        let network = build_network(&lines[2..].iter().map(|l| l.to_string()).collect::<Vec<_>>())?;
+        let start = network.id("22A").ok_or(anyhow!("missing"))?;
+        let ghost = Ghost::analyze(&network, &['L'], start)?;
+        assert_eq!((ghost.cycle_start, ghost.cycle_len), (1, 3));
+        assert_eq!(ghost.cycle_hits, vec![1]);
+        assert!(!ghost.is_simple());
+        assert!(ghost.hits_at(7) && !ghost.hits_at(6));
+        Ok(())
+    }
+
+    #[test]
+    fn test_lcm_not_valid() -> Result<()> {
+        // Ghost 1 ends at 2, 4, 6, ... and ghost 2 at
+        // 1, 4, 7, ..., so the LCM of first ends (2) is wrong...
+        let lines = [
+            "L",
+            "",
+            "11A = (11B, 11B)",
+            "11B = (11Z, 11Z)",
+            "11Z = (11B, 11B)",
+            "22A = (22Z, 22Z)",
+            "22Z = (22B, 22B)",
+            "22B = (22C, 22C)",
+            "22C = (22Z, 22Z)",
+        ];
+        assert_eq!(solve(&lines)?, Some(4));
+
+        // An end that's only reached before looping...
+        let lines = [
+            "L",
+            "",
+            "22A = (22Z, 22Z)",
+            "22Z = (22B, 22B)",
+            "22B = (22B, 22B)",
+            "33A = (33Z, 33Z)",
+            "33Z = (33Z, 33Z)",
+        ];
+        assert_eq!(solve(&lines)?, Some(1));
+
+        // Odd ends vs even ends never line up...
+        let lines = [
+            "L",
+            "",
+            "11A = (11B, 11B)",
+            "11B = (11Z, 11Z)",
+            "11Z = (11B, 11B)",
+            "33A = (33Z, 33Z)",
+            "33Z = (33B, 33B)",
+            "33B = (33Z, 33Z)",
+        ];
+        assert_eq!(solve(&lines)?, None);
+        Ok(())
+    }
+}
@@ -1,14 +1,16 @@
-use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use anyhow::Result;
+use aoc23::{parse, parse_input_lines_by_name, print_answer};
 
+/// Parses a line of space-separated numbers (and nothing
+/// else).
 fn parse_input_line(line: &str) -> Result<Vec<i32>> {
-    line.trim()
-        .split_whitespace()
-        .map(|s| {
-            s.parse::<i32>()
-                .map_err(|e| anyhow!("Failed to parse input: {}", e))
+    let nums = parse::line(line, |p| {
+        p.many(|p| {
+            p.ws();
+            p.int::<i32>()
         })
-        .collect::<Result<Vec<_>>>()
+    })?;
+    Ok(nums)
 }
 
 fn find_dists(line: &Vec<i32>) -> Vec<i32> {
@@ -32,10 +34,7 @@ fn find_next_value(line: &Vec<i32>) -> i32 {
 }
 
 fn main() -> Result<()> {
-    let data = load_input_lines_by_name(file!())?
-        .iter()
-        .map(|line| parse_input_line(line))
-        .collect::<Result<Vec<_>>>()?;
+    let data = parse_input_lines_by_name(file!(), parse_input_line)?;
     // let data = vec![
     //     vec![ 0, 3, 6, 9,12,15],
     //     vec![ 1, 3, 6,10,15,21],
@@ -47,7 +46,22 @@ fn main() -> Result<()> {
         .map(|line| find_next_value(line))
         .collect::<Vec<_>>();
     let sum = results.iter().sum::<i32>();
-    println!("Sum: {}", sum);
+    print_answer(sum);
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc23::error::ParseError;
+
+    #[test]
+    fn test_parse_input_line() -> Result<()> {
+        assert_eq!(parse_input_line("0 3 -6 9")?, vec![0, 3, -6, 9]);
+        let e = parse_input_line("1 2 x 3").unwrap_err();
+        let e = e.downcast::<ParseError>().unwrap();
+        assert_eq!((e.column, e.message.as_str()), (5, "unexpected text"));
+        Ok(())
+    }
+}
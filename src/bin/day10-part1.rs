@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::dot::{Dot, DOT_FLAG};
+use aoc23::{has_flag, load_input_lines_by_name, print_answer};
 use std::collections::HashMap;
 
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
@@ -66,6 +67,21 @@ impl TryFrom<char> for Pipe {
     }
 }
 
+impl From<Pipe> for char {
+    fn from(pipe: Pipe) -> char {
+        match pipe {
+            Pipe::Start => 'S',
+            Pipe::Ground => '.',
+            Pipe::NorthSouth => '|',
+            Pipe::EastWest => '-',
+            Pipe::NorthEast => 'L',
+            Pipe::NorthWest => 'J',
+            Pipe::SouthEast => 'F',
+            Pipe::SouthWest => '7',
+        }
+    }
+}
+
 /// Finds the starting point in the grid.
 fn find_start(grid: &Vec<Vec<Pipe>>) -> Result<Point> {
     for (y, row) in grid.iter().enumerate() {
@@ -152,6 +168,33 @@ fn get_connections(grid: &Vec<Vec<Pipe>>, point: &Point) -> Option<(Point, Point
     }
 }
 
+/// Builds a DOT graph of every pipe in the grid, with
+/// an edge between each pair of pipes that connect to each
+/// other and the points in `path` highlighted.
+fn build_dot(grid: &Vec<Vec<Pipe>>, path: &[Point]) -> Dot {
+    let id = |p: &Point| Dot::cell_id(p.y as usize, p.x as usize);
+    let mut dot = Dot::new(false);
+    for (y, row) in grid.iter().enumerate() {
+        for (x, pipe) in row.iter().enumerate() {
+            if *pipe == Pipe::Ground {
+                continue;
+            }
+            let point = Point::new(x as i32, y as i32);
+            dot.grid_node(&id(&point), &char::from(*pipe).to_string(), y, x);
+
+            // Only look east and south so each edge is added once...
+            for other in [point.east(), point.south()] {
+                if touches(grid, &point, &other) && touches(grid, &other, &point) {
+                    dot.edge(&id(&point), &id(&other), None);
+                }
+            }
+        }
+    }
+    let path = path.iter().map(id).collect::<Vec<_>>();
+    dot.highlight_path(&path);
+    dot
+}
+
 fn main() -> Result<()> {
     // Load the input data and parse it as a grid...
     let input_lines = load_input_lines_by_name(file!())?;
@@ -237,5 +280,11 @@ fn main() -> Result<()> {
         furthest_point, furthest_dist
     );
 
+    // Optionally write out the pipes with the loop highlighted...
+    if has_flag(DOT_FLAG) {
+        build_dot(&grid, &path1).write_if_requested(file!())?;
+    }
+
+    print_answer(furthest_dist);
     Ok(())
 }
@@ -1,5 +1,6 @@
 use anyhow::{anyhow, Context, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::grid::{box_drawing, Color, Grid};
+use aoc23::{flag_value, load_input_lines_by_name, print_answer};
 use std::collections::HashSet;
 
 #[derive(Debug, Hash, Clone, Copy, PartialEq, Eq)]
@@ -71,7 +72,7 @@ impl TryFrom<char> for Pipe {
 }
 
 /// Finds the starting point in the grid.
-fn find_start(grid: &Vec<Vec<Pipe>>) -> Result<Point> {
+fn find_start(grid: &[Vec<Pipe>]) -> Result<Point> {
     for (y, row) in grid.iter().enumerate() {
         for (x, pipe) in row.iter().enumerate() {
             if let Pipe::Start = pipe {
@@ -85,7 +86,7 @@ fn find_start(grid: &Vec<Vec<Pipe>>) -> Result<Point> {
 /// Gets the pipe at the given point.
 ///
 /// Returns `None` if the point is out of bounds.
-fn get_point(grid: &Vec<Vec<Pipe>>, point: &Point) -> Option<Pipe> {
+fn get_point(grid: &[Vec<Pipe>], point: &Point) -> Option<Pipe> {
     if point.y < 0 || point.y >= grid.len() as i32 {
         return None;
     }
@@ -98,7 +99,7 @@ fn get_point(grid: &Vec<Vec<Pipe>>, point: &Point) -> Option<Pipe> {
 }
 
 /// Checks if the two points touch.
-fn touches(grid: &Vec<Vec<Pipe>>, p1: &Point, p2: &Point) -> bool {
+fn touches(grid: &[Vec<Pipe>], p1: &Point, p2: &Point) -> bool {
     let c1 = get_connections(grid, p1);
     if let Some((c1, c2)) = c1 {
         c1 == *p2 || c2 == *p2
@@ -110,7 +111,7 @@ fn touches(grid: &Vec<Vec<Pipe>>, p1: &Point, p2: &Point) -> bool {
 /// Gets the connections for the given point.
 ///
 /// Returns `None` if the point is out of bounds.
-fn get_connections(grid: &Vec<Vec<Pipe>>, point: &Point) -> Option<(Point, Point)> {
+fn get_connections(grid: &[Vec<Pipe>], point: &Point) -> Option<(Point, Point)> {
     match get_point(grid, point)? {
         Pipe::Start => {
             // Get n/s/e/w points...
@@ -157,7 +158,7 @@ fn get_connections(grid: &Vec<Vec<Pipe>>, point: &Point) -> Option<(Point, Point
 }
 
 /// Infers the pipe type at `point`, given it's neighbors.
-fn infer(grid: &Vec<Vec<Pipe>>, point: &Point) -> Result<Pipe> {
+fn infer(grid: &[Vec<Pipe>], point: &Point) -> Result<Pipe> {
     let (a, b) = get_connections(grid, point)
         .ok_or(anyhow!("Unable to find connections to point {}", point))?;
 
@@ -195,31 +196,45 @@ fn infer(grid: &Vec<Vec<Pipe>>, point: &Point) -> Result<Pipe> {
     Err(anyhow!("Unable to infer pipe type at {}", point))
 }
 
-fn main() -> Result<()> {
-    // Load the input data and parse it as a grid...
-    let input_lines = load_input_lines_by_name(file!())?;
-    // let input_lines = vec![
-    //     "FF7FSF7F7F7F7F7F---7".to_string(),
-    //     "L|LJ||||||||||||F--J".to_string(),
-    //     "FL-7LJLJ||||||LJL-77".to_string(),
-    //     "F--JF--7||LJLJ7F7FJ-".to_string(),
-    //     "L---JF-JLJ.||-FJLJJ7".to_string(),
-    //     "|F|F-JF---7F7-L7L|7|".to_string(),
-    //     "|FFJF7L7F-JF7|JL---7".to_string(),
-    //     "7-L-JL7||F7|L7F-7F7|".to_string(),
-    //     "L.L7LFJ|||||FJL7||LJ".to_string(),
-    //     "L7JLJL-JLJLJL--JLJ.L".to_string(),
-    // ];
-
-    let grid = input_lines
+/// How to count the tiles enclosed by the loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Method {
+    /// Sweep each row, flipping in/out when crossing the loop.
+    Scanline,
+
+    /// Get the loop's area from its corners with the shoelace
+    /// formula, then the interior points with Pick's theorem.
+    Shoelace,
+}
+
+impl TryFrom<&str> for Method {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self> {
+        match s {
+            "scanline" => Ok(Method::Scanline),
+            "shoelace" => Ok(Method::Shoelace),
+            _ => Err(anyhow!("Unknown method \"{}\" (expected scanline or shoelace)", s)),
+        }
+    }
+}
+
+/// Parses the input lines as a grid of pipes.
+fn parse_grid(lines: &[String]) -> Result<Vec<Vec<Pipe>>> {
+    lines
         .iter()
         .map(|line| {
             line.chars()
-                .map(|c| Pipe::try_from(c))
+                .map(Pipe::try_from)
                 .collect::<Result<Vec<Pipe>>>()
         })
-        .collect::<Result<Vec<Vec<Pipe>>>>()?;
+        .collect::<Result<Vec<Vec<Pipe>>>>()
+}
 
+/// Replaces the start pipe with its inferred type and
+/// returns the points on the loop, in order, starting
+/// (but not ending) with the start point.
+fn find_loop(grid: Vec<Vec<Pipe>>) -> Result<(Vec<Vec<Pipe>>, Vec<Point>)> {
     // Find the starting point...
     let start = find_start(&grid)?;
 
@@ -235,31 +250,27 @@ fn main() -> Result<()> {
     // Find the connected points...
     let (next, _) = get_connections(&grid, &start).ok_or(anyhow!("No connections found"))?;
 
-    // Follow the two paths back to the start...
-    let path_points: HashSet<Point> = {
-        let mut path = vec![start, next];
-        let mut prev = start;
-        let mut this = next;
-        while this != start {
-            let (a, b) = get_connections(&grid, &this).ok_or(anyhow!("No connections found"))?;
-            if a != prev {
-                path.push(a);
-                prev = this;
-                this = a;
-            } else {
-                path.push(b);
-                prev = this;
-                this = b;
-            }
-        }
-        path.iter().copied().collect()
-    };
+    // Follow the path back to the start...
+    let mut path = vec![start];
+    let mut prev = start;
+    let mut this = next;
+    while this != start {
+        path.push(this);
+        let (a, b) = get_connections(&grid, &this).ok_or(anyhow!("No connections found"))?;
+        let next = if a != prev { a } else { b };
+        prev = this;
+        this = next;
+    }
+    Ok((grid, path))
+}
 
+/// Splits the points that aren't on the loop into the ones
+/// inside and outside of it, by sweeping across each row.
+fn scanline(grid: &[Vec<Pipe>], path_points: &HashSet<Point>) -> Result<(HashSet<Point>, HashSet<Point>)> {
     // Create sets to track points inside/outside
     // the path...
     let mut points_out = HashSet::<Point>::new();
     let mut points_in = HashSet::<Point>::new();
-    // let mut points_unsure = HashSet::<Point>::new();
 
     // Iterate through the grid...
     //
@@ -356,7 +367,7 @@ fn main() -> Result<()> {
                                 mark_in = !mark_in;
                             }
                             Pipe::NorthEast => {} // no-op
-                            _ => unreachable!("How'd we get here!? Was {:?} is {:?}", from, pipe),
+                            _ => return Err(anyhow!("Unexpected {:?} after {:?} at {}", pipe, from, point)),
                         }
                     }
                     Pipe::SouthWest => {
@@ -368,13 +379,10 @@ fn main() -> Result<()> {
                                 mark_in = !mark_in;
                             }
                             Pipe::SouthEast => {} // no-op
-                            _ => unreachable!("How'd we get here!? Was {:?} is {:?}", from, pipe),
+                            _ => return Err(anyhow!("Unexpected {:?} after {:?} at {}", pipe, from, point)),
                         }
                     }
-                    _ => unreachable!(
-                        "Ground and start pipes should not be in the path: {:?}",
-                        pipe
-                    ),
+                    _ => return Err(anyhow!("Unexpected {:?} on the path at {}", pipe, point)),
                 }
                 continue;
             }
@@ -387,6 +395,54 @@ fn main() -> Result<()> {
             }
         }
     }
+    Ok((points_in, points_out))
+}
+
+/// Counts the points inside the loop using the shoelace
+/// formula and Pick's theorem.
+///
+/// The shoelace formula gives the loop's area from its
+/// corners. Pick's theorem says `A = i + b/2 - 1` for a
+/// polygon on the grid, where `b` (the points on the
+/// boundary) is the loop's length, so `i = A - b/2 + 1`.
+fn shoelace(grid: &[Vec<Pipe>], path: &[Point]) -> usize {
+    // Only the corners matter...
+    let vertices = path
+        .iter()
+        .filter(|p| {
+            !matches!(
+                get_point(grid, p),
+                Some(Pipe::NorthSouth) | Some(Pipe::EastWest)
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Twice the area...
+    let area2 = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| a.x as i64 * b.y as i64 - b.x as i64 * a.y as i64)
+        .sum::<i64>()
+        .unsigned_abs() as usize;
+
+    // (Twice) Pick's theorem...
+    (area2 + 2 - path.len()) / 2
+}
+
+fn main() -> Result<()> {
+    // Load the input data and parse it as a grid...
+    let input_lines = load_input_lines_by_name(file!())?;
+    let grid = parse_grid(&input_lines)?;
+
+    // Pick how to count the enclosed points...
+    let method = match flag_value("--method") {
+        Some(m) => Method::try_from(m.as_str())?,
+        None => Method::Scanline,
+    };
+
+    // Find the loop...
+    let (grid, path) = find_loop(grid)?;
+    let path_points = path.iter().copied().collect::<HashSet<_>>();
 
     // Get the grid size...
     let height = grid.len();
@@ -394,8 +450,107 @@ fn main() -> Result<()> {
     let n_points_total = width * height;
     println!("Points total = {}", n_points_total);
     println!("Points on path = {}", path_points.len());
-    println!("Points outside path = {}", points_out.len());
-    println!("Points inside path = {}", points_in.len());
 
+    // Count the points inside the loop...
+    let cell = |p: &Point| (p.y as usize, p.x as usize);
+    let mut overlay = Grid::from_lines(&input_lines);
+    overlay
+        .map_chars(box_drawing)
+        .overlay(path_points.iter().map(cell), None, Some(Color::Yellow));
+    let n_in = match method {
+        Method::Scanline => {
+            let (points_in, points_out) = scanline(&grid, &path_points)?;
+            println!("Points outside path = {}", points_out.len());
+            println!("Points inside path = {}", points_in.len());
+            overlay
+                .overlay(points_out.iter().map(cell), Some('O'), Some(Color::Gray))
+                .overlay(points_in.iter().map(cell), Some('I'), Some(Color::Green));
+            points_in.len()
+        }
+        Method::Shoelace => {
+            let n_in = shoelace(&grid, &path);
+            println!("Points outside path = {}", n_points_total - path.len() - n_in);
+            println!("Points inside path = {}", n_in);
+            n_in
+        }
+    };
+
+    // Optionally draw the loop (and the points in/out of it)...
+    overlay.print_if_requested();
+
+    print_answer(n_in);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_both(lines: &[&str]) -> Result<(usize, usize)> {
+        let lines = lines.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        let (grid, path) = find_loop(parse_grid(&lines)?)?;
+        let path_points = path.iter().copied().collect::<HashSet<_>>();
+        let (points_in, _) = scanline(&grid, &path_points)?;
+        Ok((points_in.len(), shoelace(&grid, &path)))
+    }
+
+    #[test]
+    fn test_methods_agree() -> Result<()> {
+        let examples: [(&[&str], usize); 3] = [
+            (
+                &[
+                    "...........",
+                    ".S-------7.",
+                    ".|F-----7|.",
+                    ".||.....||.",
+                    ".||.....||.",
+                    ".|L-7.F-J|.",
+                    ".|..|.|..|.",
+                    ".L--J.L--J.",
+                    "...........",
+                ],
+                4,
+            ),
+            (
+                &[
+                    ".F----7F7F7F7F-7....",
+                    ".|F--7||||||||FJ....",
+                    ".||.FJ||||||||L7....",
+                    "FJL7L7LJLJ||LJ.L-7..",
+                    "L--J.L7...LJS7F-7L7.",
+                    "....F-J..F7FJ|L7L7L7",
+                    "....L7.F7||L7|.L7L7|",
+                    ".....|FJLJ|FJ|F7|.LJ",
+                    "....FJL-7.||.||||...",
+                    "....L---J.LJ.LJLJ...",
+                ],
+                8,
+            ),
+            (
+                &[
+                    "FF7FSF7F7F7F7F7F---7",
+                    "L|LJ||||||||||||F--J",
+                    "FL-7LJLJ||||||LJL-77",
+                    "F--JF--7||LJLJ7F7FJ-",
+                    "L---JF-JLJ.||-FJLJJ7",
+                    "|F|F-JF---7F7-L7L|7|",
+                    "|FFJF7L7F-JF7|JL---7",
+                    "7-L-JL7||F7|L7F-7F7|",
+                    "L.L7LFJ|||||FJL7||LJ",
+                    "L7JLJL-JLJLJL--JLJ.L",
+                ],
+                10,
+            ),
+        ];
+        for (lines, expected) in examples {
+            assert_eq!(count_both(lines)?, (expected, expected));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_method_parse() {
+        assert_eq!(Method::try_from("shoelace").ok(), Some(Method::Shoelace));
+        assert!(Method::try_from("flood").is_err());
+    }
+}
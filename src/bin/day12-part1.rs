@@ -1,5 +1,7 @@
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::nonogram::{count_arrangements, Cell, Puzzle, Solution};
+use aoc23::error::ParseError;
+use aoc23::{flag_value, parse_input_lines_by_name, print_answer};
 
 #[derive(Debug,Clone,PartialEq,Eq)]
 enum SpringState {
@@ -8,6 +10,16 @@ enum SpringState {
     Unknown,
 }
 
+impl From<&SpringState> for Cell {
+    fn from(s: &SpringState) -> Cell {
+        match s {
+            SpringState::Operational => Cell::Empty,
+            SpringState::Damaged => Cell::Filled,
+            SpringState::Unknown => Cell::Unknown,
+        }
+    }
+}
+
 impl TryFrom<char> for SpringState {
     type Error = anyhow::Error;
 
@@ -38,131 +50,63 @@ impl Line {
 
         let springs = parts
             .get(0)
-            .ok_or(anyhow!("No springs found in line: {}", line))?
+            .ok_or_else(|| ParseError::new(line, "No springs found"))?
             .chars()
-            .map(|c| SpringState::try_from(c))
-            .collect::<Result<Vec<_>>>()?;
+            .enumerate()
+            .map(|(i, c)| SpringState::try_from(c)
+                .map_err(|e| ParseError::at(line, i + 1, e.to_string()))
+            )
+            .collect::<Result<Vec<_>, _>>()?;
         let damage_counts = parts
             .get(1)
-            .ok_or(anyhow!("No damage counts found in line: {}", line))?
+            .ok_or_else(|| ParseError::at(line, line.chars().count() + 1, "No damage counts found"))?
             .split(",")
             .map(|s| s
                 .parse::<usize>()
-                .map_err(anyhow::Error::msg)
+                .map_err(|e| ParseError::within(line, s, e.to_string()))
             )
-            .collect::<Result<Vec<_>>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(Self::new(
             springs,
             damage_counts,
         ))
     }
 
-    fn get_real_damage_counts(&self) -> Option<Vec<usize>> {
-        let mut found = Vec::<usize>::new();
-        let mut start: Option<usize> = None;
-
-        for (i, s) in self.springs.iter().enumerate() {
-            match *s {
-                SpringState::Operational => {
-                    if let Some(start_pos) = start {
-                        found.push(i - start_pos);
-                        start = None;
-                    }
-                },
-                SpringState::Damaged => {
-                    if start.is_none() {
-                        start = Some(i);
-                    }
-                },
-                SpringState::Unknown => {
-                    return None;
-                },
-            }
-        }
-        if let Some(start_pos) = start {
-            found.push(self.springs.len() - start_pos);
-        }
-        Some(found)
-    }
-    
-    fn matches(&self, other: &Self) -> Option<bool> {
-        let real_damage_counts = self.get_real_damage_counts()?;
-        if real_damage_counts.len() != other.damage_counts.len() {
-            return Some(false);
-        }
-        if real_damage_counts.iter().sum::<usize>() != other.damage_counts.iter().sum::<usize>() {
-            return Some(false);
-        }
-        for (i, count) in real_damage_counts.iter().enumerate() {
-            let other_count = other.damage_counts.get(i);
-            if other_count.is_none() {
-                return Some(false);
-            }
-            let other_count = other_count.unwrap();
-            if *count != *other_count {
-                return Some(false);
-            }
-        }
-        Some(true)
+    fn arrangement_count(&self) -> usize {
+        let cells = self.springs.iter().map(Cell::from).collect::<Vec<_>>();
+        count_arrangements(&cells, &self.damage_counts) as usize
     }
+}
 
-    fn with_set_next_spring(&self, state: SpringState) -> Self {
-        let mut springs = self.springs.clone();
-        for i in 0..springs.len() {
-            if springs[i] == SpringState::Unknown {
-                springs[i] = state;
-                break;
-            }
+/// Solves the nonogram in the clue file at `path`.
+fn solve_nonogram(path: &str) -> Result<()> {
+    let text = std::fs::read_to_string(path)?;
+    let puzzle = Puzzle::parse(&text)?;
+    match puzzle.solve() {
+        Solution::None => println!("No solution"),
+        Solution::Unique(board) => print!("{}", board),
+        Solution::Multiple(a, b) => {
+            println!("More than one solution, eg:\n{}\n{}", a, b);
         }
-        let mut line = self.clone();
-        line.springs = springs;
-        line
-    }
-
-    fn arrangement_count(&self) -> usize {
-        let n_spaces = self.springs
-            .iter()
-            .filter(|s| **s == SpringState::Unknown)
-            .count();
-        let mut possible_routes = vec![self.clone()];
-        for _ in 0..n_spaces {
-            possible_routes = possible_routes
-                .iter()
-                .flat_map(|line| {
-                    vec![
-                        line.with_set_next_spring(SpringState::Operational),
-                        line.with_set_next_spring(SpringState::Damaged),
-                    ]
-                })
-                .collect::<Vec<_>>();
-        }
-        possible_routes
-            .iter()
-            .filter(|line| {
-                let m = line.matches(self);
-                if let Some(m) = m {
-                    m
-                } else {
-                    false
-                }
-            })
-            .count()
     }
+    Ok(())
 }
 
 fn main() -> Result<()> {
-    let input = load_input_lines_by_name(file!())?;
-    let lines = input
-        .iter()
-        .map(|line| Line::parse(line))
-        .collect::<Result<Vec<_>>>()?;
+    // Optionally solve a whole nonogram instead, using
+    // the same line logic...
+    if let Some(path) = flag_value("--nonogram") {
+        return solve_nonogram(&path);
+    }
+
+    let lines = parse_input_lines_by_name(file!(), Line::parse)?;
 
     let total = lines
         .iter()
         .map(|line| line.arrangement_count())
         .sum::<usize>();
     
-    println!("Total arrangements: {}", total);
+    print_answer(total);
     Ok(())
 }
 
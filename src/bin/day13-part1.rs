@@ -1,4 +1,6 @@
 use anyhow::{anyhow, Result};
+use aoc23::input::paragraphs;
+use aoc23::print_answer;
 
 #[derive(Debug,Clone,Copy,PartialEq,Eq)]
 enum Square {
@@ -44,7 +46,7 @@ impl Pattern {
         Self { width, height, grid }
     }
 
-    fn parse(input: &Vec<String>) -> Result<Self> {
+    fn parse(input: &[&str]) -> Result<Self> {
         let grid = input
             .iter()
             .map(|line| line
@@ -149,12 +151,12 @@ impl Pattern {
 
 fn main() -> Result<()> {
     // Load in the input data...
-    let input = aoc23::load_input_lines_by_name(file!())?;
+    let input = aoc23::load_input_by_name(file!())?;
 
-    // Split the vec of input lines on empty lines...
-    let patterns = input
-        .split(|line| line.is_empty())
-        .map(|lines| Pattern::parse(&lines.to_vec()))
+    // Split the input lines on empty lines...
+    let patterns = paragraphs(&input)
+        .iter()
+        .map(|lines| Pattern::parse(lines))
         .collect::<Result<Vec<_>>>()?;
 
     // Summarize the patterns...
@@ -165,7 +167,7 @@ fn main() -> Result<()> {
 
     // Get the summary sum...
     let sum = summary.iter().sum::<usize>();
-    println!("sum: {}", sum);
+    print_answer(sum);
 
     Ok(())
 }
@@ -1,5 +1,6 @@
 use anyhow::{anyhow,Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::{load_input_lines_by_name, print_answer};
+use aoc23::sim::{self, Simulation};
 
 #[derive(Clone,Copy,PartialEq)]
 enum Space {
@@ -49,7 +50,7 @@ impl Grid {
         Ok(Grid { spaces })
     }
 
-    fn shift_north_once(&mut self) -> Result<bool> {
+    fn shift_north_once(&mut self) -> bool {
         // Track if anything has moved...
         let mut shifted = false;
 
@@ -99,12 +100,7 @@ impl Grid {
         }
         
         // Return the shifted flag...
-        Ok(shifted)
-    }
-
-    fn shift_north_until_settled(&mut self) -> Result<()> {
-        while self.shift_north_once()? {}
-        Ok(())
+        shifted
     }
 
     fn get_load(&self) -> usize {
@@ -121,22 +117,61 @@ impl Grid {
     }
 }
 
+impl Simulation for Grid {
+    fn step(&mut self) -> bool {
+        self.shift_north_once()
+    }
+
+    fn render(&self) -> String {
+        self.spaces
+            .iter()
+            .map(|row| row.iter().map(|s| s.to_string()).collect::<String>() + "\n")
+            .collect()
+    }
+}
+
 fn main() -> Result<()> {
     // Load the input liles...
     let input = load_input_lines_by_name(file!())?;
 
     // Parse it as a grid...
-    let mut grid = Grid::parse(&input)?;
+    let grid = Grid::parse(&input)?;
 
-    // Shift all the round grid stones north until they settle...
-    grid.shift_north_until_settled()?;
+    // Shift all the round grid stones north until they
+    // settle (optionally animating it)...
+    let grid = sim::run(grid, file!())?;
 
     // Get the load...
     let load = grid.get_load();
 
     // Print the load...
-    println!("The load is: {}", load);
+    print_answer(load);
 
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() -> Result<()> {
+        let input = vec![
+            "O....#....".to_string(),
+            "O.OO#....#".to_string(),
+            ".....##...".to_string(),
+            "OO.#O....O".to_string(),
+            ".O.....O#.".to_string(),
+            "O.#..O.#.#".to_string(),
+            "..O..#O..O".to_string(),
+            ".......O..".to_string(),
+            "#....###..".to_string(),
+            "#OO..#....".to_string(),
+        ];
+        let mut grid = Grid::parse(&input)?;
+        assert_eq!(grid.render().lines().collect::<Vec<_>>(), input);
+        while grid.step() {}
+        assert_eq!(grid.get_load(), 136);
+        Ok(())
+    }
+}
@@ -1,41 +1,23 @@
-use anyhow::{anyhow,Result};
-use aoc23::load_input_by_name;
-
-fn get_ascii_code(c: char) -> u32 {
-    c as u32
-}
-
-fn hash_char(state: u32, c: char) -> u32 {
-    let ascii_code = get_ascii_code(c);
-    let next = state + ascii_code;
-    let next = next * 17;
-    let next = next % 256;
-    next
-}
-
-fn hash_string(s: &str) -> u32 {
-    let mut state = 0;
-    for c in s.chars() {
-        state = hash_char(state, c);
-    }
-    state
-}
+use anyhow::Result;
+use aoc23::holiday::hash;
+use aoc23::input::Normalize;
+use aoc23::{load_input_by_name_with, print_answer};
 
 fn main() -> Result<()> {
-    let input = load_input_by_name(file!())?;
-    let parts = input
-        .split(",")
-        .collect::<Vec<_>>();
-    let scores = parts
-        .iter()
+    // (Trimming the end, so the last step doesn't pick up
+    // a line ending)...
+    let input = load_input_by_name_with(file!(), Normalize::new().trim_end())?;
+
+    // (Newlines in the sequence are ignored, and so are
+    // stray spaces and empty steps)...
+    let input = input.replace('\n', "");
+    let total = input
+        .split(',')
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
-        .map(|s| hash_string(s))
-        .collect::<Vec<_>>();
-    let total = scores
-        .iter()
+        .map(|s| hash(s) as u32)
         .sum::<u32>();
-    println!("Total: {}", total);
+    print_answer(total);
     Ok(())
 }
 
@@ -43,27 +25,10 @@ fn main() -> Result<()> {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_get_ascii_code() {
-        assert_eq!(get_ascii_code('a'), 97);
-        assert_eq!(get_ascii_code('A'), 65);
-        assert_eq!(get_ascii_code('1'), 49);
-        assert_eq!(get_ascii_code('!'), 33);
-    }
-    
-    #[test]
-    fn test_hash_char() {
-        assert_eq!(hash_char(  0, 'H'), 200);
-        assert_eq!(hash_char(200, 'A'), 153);
-        assert_eq!(hash_char(153, 'S'), 172);
-        assert_eq!(hash_char(172, 'H'), 52);
-    }
-    
     #[test]
     fn test_hash_string() {
-        assert_eq!(hash_string("rn=1"), 30);
-        assert_eq!(hash_string("cm-"), 253);
-        assert_eq!(hash_string("qp=3"), 97);
+        assert_eq!(hash("rn=1"), 30);
+        assert_eq!(hash("cm-"), 253);
+        assert_eq!(hash("qp=3"), 97);
     }
 }
-
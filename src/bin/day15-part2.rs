@@ -1,172 +1,90 @@
-use anyhow::{anyhow,Result};
-use aoc23::load_input_by_name;
+use anyhow::{anyhow, Result};
+use aoc23::holiday::BoxMap;
+use aoc23::input::Normalize;
+use aoc23::{load_input_by_name_with, print_answer};
 
-#[derive(Debug,Clone,PartialEq,Eq)]
-struct Lens {
-    label: String,
-    focal_length: u32,
-}
-
-impl Lens {
-    fn new(label: &str, focal_length: u32) -> Self {
-        Self {
-            label: label.to_string(),
-            focal_length,
-        }
-    }
-
-    fn parse(s: &str) -> Result<Self> {
-        let parts = s
-            .split("=")
-            .collect::<Vec<_>>();
-        if parts.len() != 2 {
-            return Err(anyhow!("Invalid lens: {}", s));
-        }
-        let label = parts[0].trim();
-        let focal_length = parts[1]
-            .trim()
-            .parse::<u32>()?;
-        Ok(Self::new(label, focal_length))
-    }
-
-    fn get_hash(&self) -> u32 {
-        hash_string(&self.label)
-    }
-}
-
-#[derive(Debug,Clone,PartialEq,Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 enum Operation {
-    Set(Lens),
+    /// Put a lens with the focal length in the label's box.
+    Set(String, u32),
+
+    /// Take the lens with the label out of its box.
     Unset(String),
 }
 
 impl Operation {
     fn parse(s: &str) -> Result<Self> {
         // If it's a set command, it will contain an
-        // equal sign. Parse it with the Lens parser
-        // and return...
-        if s.contains('=') {
-            let lens = Lens::parse(s)?;
-            return Ok(Self::Set(lens))
+        // equal sign...
+        if let Some((label, focal_length)) = s.split_once('=') {
+            let focal_length = focal_length.trim().parse::<u32>()?;
+            return Ok(Self::Set(label.trim().to_string(), focal_length));
         }
-        
+
         // Otherwise, it must be an unset command
-        // (meaning it ends with an '-'). Get the
-        // characters up to the dash and return...
+        // (meaning it ends with an '-')...
         let label = s
-            .split("-")
-            .next()
-            .ok_or_else(|| anyhow!("Invalid unset command: {}", s))?;
+            .strip_suffix('-')
+            .ok_or_else(|| anyhow!("Invalid command: {}", s))?;
         Ok(Self::Unset(label.to_string()))
     }
 
-    fn get_hash(&self) -> u32 {
+    fn apply(&self, boxes: &mut BoxMap<u32>) {
         match self {
-            Self::Set(lens) => lens.get_hash(),
-            Self::Unset(label) => hash_string(label),
+            Self::Set(label, focal_length) => {
+                boxes.insert(label, *focal_length);
+            }
+            Self::Unset(label) => {
+                boxes.remove(label);
+            }
         }
     }
 }
 
-fn get_ascii_code(c: char) -> u32 {
-    c as u32
-}
-
-fn hash_char(state: u32, c: char) -> u32 {
-    let ascii_code = get_ascii_code(c);
-    let next = state + ascii_code;
-    let next = next * 17;
-    let next = next % 256;
-    next
-}
-
-fn hash_string(s: &str) -> u32 {
-    let mut state = 0;
-    for c in s.chars() {
-        state = hash_char(state, c);
-    }
-    state
-}
-
-fn init_state() -> Vec<Vec<Lens>> {
-    (0..256)
-        .map(|_| Vec::<Lens>::new())
-        .collect::<Vec<_>>()
-}
-
-fn update_state(state: &mut Vec<Vec<Lens>>, c: &Operation) {
-    let box_idx = c.get_hash() as usize;
-    match c {
-        Operation::Unset(label) => {
-            state[box_idx] = state[box_idx]
+/// Adds up each lens's box number times its slot number
+/// times its focal length (all counting from 1).
+fn focusing_power(boxes: &BoxMap<u32>) -> u32 {
+    boxes
+        .buckets()
+        .flat_map(|(i, lenses)| {
+            lenses
                 .iter()
-                .filter(|l| l.label != *label)
-                .cloned()
-                .collect::<Vec<_>>();
-        },
-        Operation::Set(lens) => {
-            if !state[box_idx].iter().any(|l| l.label == lens.label) {
-                state[box_idx].push(lens.clone());
-            } else {
-                state[box_idx] = state[box_idx]
-                    .iter()
-                    .map(|l| {
-                        if l.label == lens.label {
-                            lens.clone()
-                        } else {
-                            l.clone()
-                        }
-                    })
-                    .collect::<Vec<_>>();
-            }
-        },
-    }
-}
-
-fn score_state(state: &Vec<Vec<Lens>>) -> u32 {
-    state
-        .iter()
-        .enumerate()
-        .map(|(i, b)| {
-            b.iter()
                 .enumerate()
-                .map(|(j, l)| {
-                    let a = (i + 1) as u32;
-                    let b = (j + 1) as u32;
-                    let c = l.focal_length;
-                    a * b * c
-                })
-                .sum::<u32>()
+                .map(move |(j, (_, f))| (i as u32 + 1) * (j as u32 + 1) * f)
         })
-        .sum::<u32>()
+        .sum()
 }
 
 fn main() -> Result<()> {
-    let input = load_input_by_name(file!())?;
+    // (Trimming the end, so the last step doesn't pick up
+    // a line ending)...
+    let input = load_input_by_name_with(file!(), Normalize::new().trim_end())?;
+
+    // (Newlines in the sequence are ignored, and so are
+    // stray spaces and empty steps)...
+    let input = input.replace('\n', "");
     let parts = input
-        .split(",")
+        .split(',')
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
         .collect::<Vec<_>>();
     println!("There are {} parts", parts.len());
-    
+
     // Parse the commands...
     let commands = parts
         .iter()
         .map(|s| Operation::parse(s))
         .collect::<Result<Vec<_>>>()?;
-    
-    // Initialize the state...
-    let mut state = init_state();
 
-    // Loop through the commands...
-    for c in commands {
-        update_state(&mut state, &c);
+    // Run them against the boxes...
+    let mut boxes = BoxMap::new();
+    for c in &commands {
+        c.apply(&mut boxes);
     }
 
     // Score the result...
-    let score = score_state(&state);
-    println!("Score: {}", score);
+    let score = focusing_power(&boxes);
+    print_answer(score);
 
     Ok(())
 }
@@ -176,82 +94,34 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_get_ascii_code() {
-        assert_eq!(get_ascii_code('a'), 97);
-        assert_eq!(get_ascii_code('A'), 65);
-        assert_eq!(get_ascii_code('1'), 49);
-        assert_eq!(get_ascii_code('!'), 33);
-    }
-    
-    #[test]
-    fn test_hash_char() {
-        assert_eq!(hash_char(  0, 'H'), 200);
-        assert_eq!(hash_char(200, 'A'), 153);
-        assert_eq!(hash_char(153, 'S'), 172);
-        assert_eq!(hash_char(172, 'H'), 52);
-    }
-    
-    #[test]
-    fn test_hash_string() {
-        assert_eq!(hash_string("rn=1"), 30);
-        assert_eq!(hash_string("cm-"), 253);
-        assert_eq!(hash_string("qp=3"), 97);
+    fn test_operation_parse() -> Result<()> {
+        assert_eq!(
+            Operation::parse("rn=1")?,
+            Operation::Set("rn".to_string(), 1)
+        );
+        assert_eq!(Operation::parse("cm-")?, Operation::Unset("cm".to_string()));
+        assert!(Operation::parse("cm").is_err());
+        assert!(Operation::parse("cm=x").is_err());
+        Ok(())
     }
 
     #[test]
-    fn test_update_state() {
-        let mut state = init_state();
-        let c = Operation::parse("rn=1").unwrap();
-        update_state(&mut state, &c);
-        assert_eq!(state[0], vec![Lens::new("rn", 1)]);
-        assert_eq!(state.iter().filter(|b| !b.is_empty()).count(), 1);
-
-        let c = Operation::parse("cm-").unwrap();
-        update_state(&mut state, &c);
-        assert_eq!(state[0], vec![Lens::new("rn", 1)]);
-        assert_eq!(state.iter().filter(|b| !b.is_empty()).count(), 1);
-
-        let c = Operation::parse("qp=3").unwrap();
-        update_state(&mut state, &c);
-        assert_eq!(state[0], vec![Lens::new("rn", 1)]);
-        assert_eq!(state[1], vec![Lens::new("qp", 3)]);
-        assert_eq!(state.iter().filter(|b| !b.is_empty()).count(), 2);
-
-        let c = Operation::parse("cm=2").unwrap();
-        update_state(&mut state, &c);
-        assert_eq!(state[0], vec![Lens::new("rn", 1),Lens::new("cm", 2)]);
-        assert_eq!(state[1], vec![Lens::new("qp", 3)]);
-        assert_eq!(state.iter().filter(|b| !b.is_empty()).count(), 2);
-
-        let c = Operation::parse("qp-").unwrap();
-        update_state(&mut state, &c);
-        assert_eq!(state[0], vec![Lens::new("rn", 1),Lens::new("cm", 2)]);
-        assert_eq!(state[1], vec![]);
-        assert_eq!(state.iter().filter(|b| !b.is_empty()).count(), 1);
-
-        let c = Operation::parse("pc=4").unwrap();
-        update_state(&mut state, &c);
-        assert_eq!(state[0], vec![Lens::new("rn", 1),Lens::new("cm", 2)]);
-        assert_eq!(state[3], vec![Lens::new("pc", 4)]);
-        assert_eq!(state.iter().filter(|b| !b.is_empty()).count(), 2);
-    }
-
-    #[test]
-    fn test_score_state() {
-        let state = vec![
-            vec![
-                Lens::new("rn", 1),
-                Lens::new("cm", 2),
-            ],
-            vec![],
-            vec![],
-            vec![
-                Lens::new("ot", 7),
-                Lens::new("ab", 5),
-                Lens::new("pc", 6),
-            ],
-        ];
-        assert_eq!(score_state(&state), 145);
+    fn test_example() -> Result<()> {
+        let mut boxes = BoxMap::new();
+        for s in "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7".split(',') {
+            Operation::parse(s)?.apply(&mut boxes);
+        }
+        let lens = |b: usize| {
+            boxes
+                .bucket(b)
+                .iter()
+                .map(|(l, f)| (l.as_str(), *f))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(lens(0), vec![("rn", 1), ("cm", 2)]);
+        assert_eq!(lens(1), vec![]);
+        assert_eq!(lens(3), vec![("ot", 7), ("ab", 5), ("pc", 6)]);
+        assert_eq!(focusing_power(&boxes), 145);
+        Ok(())
     }
 }
-
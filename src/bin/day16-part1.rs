@@ -3,7 +3,9 @@
 
 use std::collections::HashSet;
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::grid::{Color, Grid};
+use aoc23::{load_input_lines_by_name, print_answer};
+use aoc23::sim::{self, Simulation};
 
 #[derive(Debug,Clone,PartialEq,Eq,Hash)]
 enum Direction {
@@ -245,98 +247,142 @@ impl Beam {
     }
 }
 
-fn main() -> Result<()> {
-    // Parse the input as a grid of spaces...
-    let input = load_input_lines_by_name(file!())?;
-    // let input = vec![
-    //     ".|...\\....".to_string(),
-    //     "|.-.\\.....".to_string(),
-    //     ".....|-...".to_string(),
-    //     "........|.".to_string(),
-    //     "..........".to_string(),
-    //     ".........\\".to_string(),
-    //     "..../.\\\\..".to_string(),
-    //     ".-.-/..|..".to_string(),
-    //     ".|....-|.\\".to_string(),
-    //     "..//.|....".to_string(),
-    // ];
-    let grid = input
-        .iter()
-        .map(|line| line
-            .chars()
-            .map(|c| Space::try_from(c))
-            .collect::<Result<Vec<Space>>>()
-        )
-        .collect::<Result<Vec<_>>>()?;
+impl From<&Space> for char {
+    fn from(s: &Space) -> char {
+        match s {
+            Space::Empty => '.',
+            Space::MirrorForward => '/',
+            Space::MirrorBackward => '\\',
+            Space::SplitLR => '-',
+            Space::SplitUD => '|',
+        }
+    }
+}
+
+/// The beams moving through the grid, one round of
+/// steps at a time.
+struct BeamSim<'a> {
+    grid: &'a [Vec<Space>],
+    width: usize,
+    height: usize,
 
-    // Get the grid size...
-    let height = grid.len();
-    let width = grid[0].len();
+    /// The beams in the current round...
+    beams: Vec<Beam>,
 
-    // Create a vector of beams...
-    let mut beams = vec![
-        Beam::new(0, 0, Direction::Right),
-    ];
+    /// The energized spaces...
+    energized_spaces: HashSet<Pos>,
 
-    // Track the energized spaces...
-    let mut energized_spaces: HashSet<Pos> = HashSet::new();
+    /// The beam (position, direction) pairs that have
+    /// already been seen...
+    seen_beams: HashSet<Beam>,
+}
+
+impl<'a> BeamSim<'a> {
+    fn new(grid: &'a [Vec<Space>], start: Beam) -> Self {
+        Self {
+            grid,
+            width: grid[0].len(),
+            height: grid.len(),
+            beams: vec![start],
+            energized_spaces: HashSet::new(),
+            seen_beams: HashSet::new(),
+        }
+    }
+}
 
-    // Track the beam (position, direction) pairs that
-    // have already been seen...
-    let mut seen_beams: HashSet<Beam> = HashSet::new();
+impl Simulation for BeamSim<'_> {
+    fn step(&mut self) -> bool {
+        // Stop once all of the beams are gone...
+        if self.beams.is_empty() {
+            return false;
+        }
 
-    // Loop until all of the beams are gone... 
-    while beams.len() > 0 {
         // Make sure all the current beams are in the
         // energized set...
-        for beam in &beams {
-            energized_spaces.insert(beam.pos.clone());
-            seen_beams.insert(beam.clone());
+        for beam in &self.beams {
+            self.energized_spaces.insert(beam.pos.clone());
+            self.seen_beams.insert(beam.clone());
         }
 
         // Step each of the beams forward once...
-        let next = beams
+        let next = self
+            .beams
             .iter()
             .flat_map(|b| {
                 // Find the grid spot at that position
                 // (Note: They should all be in range)...
-                let s = &grid[b.pos.y][b.pos.x];
+                let s = &self.grid[b.pos.y][b.pos.x];
 
                 // Step the beam...
-                b.step(s, width, height)
+                b.step(s, self.width, self.height)
             })
             .collect::<Vec<_>>();
 
         // The next beams from this round become the beams
         // for the next round...
-        beams = next
+        self.beams = next
             .into_iter()
-            .filter(|b| !seen_beams.contains(b))
+            .filter(|b| !self.seen_beams.contains(b))
             .collect::<Vec<_>>();
+        true
     }
 
-    // Debug print the state of the board after the fact...
-    for (i, row) in grid.iter().enumerate() {
-        for (j, space) in row.iter().enumerate() {
-             if energized_spaces.contains(&Pos::new(j, i)) {
-                print!("#");
-                continue
-             }
-             match space {
-                Space::Empty => print!("."),
-                Space::MirrorForward => print!("/"),
-                Space::MirrorBackward => print!("\\"),
-                Space::SplitLR => print!("-"),
-                Space::SplitUD => print!("|"),
-             }
-        }
-        println!();
+    fn render(&self) -> String {
+        let beams = self.beams.iter().map(|b| {
+            let ch = match b.dir {
+                Direction::Up => '^',
+                Direction::Down => 'v',
+                Direction::Left => '<',
+                Direction::Right => '>',
+            };
+            ((b.pos.y, b.pos.x), ch)
+        });
+        Grid::from_cells(self.grid, |s| char::from(s))
+            .overlay(self.energized_spaces.iter().map(|p| (p.y, p.x)), Some('#'), None)
+            .overlay_chars(beams, None)
+            .render(false)
     }
+}
+
+fn main() -> Result<()> {
+    // Parse the input as a grid of spaces...
+    let input = load_input_lines_by_name(file!())?;
+    // let input = vec![
+    //     ".|...\\....".to_string(),
+    //     "|.-.\\.....".to_string(),
+    //     ".....|-...".to_string(),
+    //     "........|.".to_string(),
+    //     "..........".to_string(),
+    //     ".........\\".to_string(),
+    //     "..../.\\\\..".to_string(),
+    //     ".-.-/..|..".to_string(),
+    //     ".|....-|.\\".to_string(),
+    //     "..//.|....".to_string(),
+    // ];
+    let grid = input
+        .iter()
+        .map(|line| line
+            .chars()
+            .map(|c| Space::try_from(c))
+            .collect::<Result<Vec<Space>>>()
+        )
+        .collect::<Result<Vec<_>>>()?;
+
+    // Fire the beams until they're all gone (optionally
+    // animating them)...
+    let state = sim::run(BeamSim::new(&grid, Beam::new(0, 0, Direction::Right)), file!())?;
+    let energized_spaces = state.energized_spaces;
+    let beams = state.beams;
+
+    // Optionally draw the board with the energized spaces...
+    Grid::from_lines(&input)
+        .overlay(energized_spaces.iter().map(|p| (p.y, p.x)), Some('#'), Some(Color::Yellow))
+        .print_if_requested();
 
     // How many spots were energized?
     let count = energized_spaces.len();
-    println!("There are {} energized spaces", count);
     println!("There are {} remaining beams", beams.len());
+    print_answer(count);
 
     Ok(())
 }
@@ -3,7 +3,10 @@
 
 use std::collections::HashSet;
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::graph::Graph;
+use aoc23::{load_input_lines_by_name, print_answer};
+use std::panic;
+use std::thread;
 
 #[derive(Debug,Clone,PartialEq,Eq,Hash)]
 enum Direction {
@@ -245,7 +248,7 @@ impl Beam {
     }
 }
 
-fn get_count(grid: &Vec<Vec<Space>>, start: Beam) -> usize {
+fn get_count(grid: &[Vec<Space>], start: Beam) -> usize {
     // Get the grid size...
     let height = grid.len();
     let width = grid[0].len();
@@ -263,7 +266,7 @@ fn get_count(grid: &Vec<Vec<Space>>, start: Beam) -> usize {
     let mut seen_beams: HashSet<Beam> = HashSet::new();
 
     // Loop until all of the beams are gone... 
-    while beams.len() > 0 {
+    while !beams.is_empty() {
         // Make sure all the current beams are in the
         // energized set...
         for beam in &beams {
@@ -297,69 +300,235 @@ fn get_count(grid: &Vec<Vec<Space>>, start: Beam) -> usize {
 
 }
 
+/// A set of energized cells, as a bitset over cell indexes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Energized {
+    bits: Vec<u64>,
+}
+
+impl Energized {
+    fn new(cells: usize) -> Self {
+        Self {
+            bits: vec![0; cells.div_ceil(64)],
+        }
+    }
+
+    fn insert(&mut self, cell: usize) {
+        self.bits[cell / 64] |= 1 << (cell % 64);
+    }
+
+    fn union_with(&mut self, other: &Self) {
+        for (a, b) in self.bits.iter_mut().zip(&other.bits) {
+            *a |= b;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.bits.iter().map(|b| b.count_ones() as usize).sum()
+    }
+}
+
+/// Checks if a beam going `dir` is split by space `s`
+/// (ie hits a splitter side-on).
+fn splits(s: &Space, dir: &Direction) -> bool {
+    matches!(
+        (s, dir),
+        (Space::SplitLR, Direction::Up | Direction::Down)
+            | (Space::SplitUD, Direction::Left | Direction::Right)
+    )
+}
+
+/// The path of a single beam from where it starts until it
+/// leaves the grid, loops back on itself or gets split.
+struct Segment {
+    /// The cells the beam passes through (as indexes).
+    cells: Vec<usize>,
+
+    /// The cell of the splitter that ends the segment.
+    splitter: Option<usize>,
+}
+
+/// The beams through a grid, precomputed as segments
+/// between splitters.
+///
+/// A splitter hit side-on always sends out the same two
+/// beams, no matter where the beam that hit it came from,
+/// so everything energized from there on can be worked out
+/// once per splitter. Splitters that feed each other are in
+/// the same strongly connected component and energize the
+/// same cells.
+struct BeamGraph<'a> {
+    grid: &'a [Vec<Space>],
+    width: usize,
+    height: usize,
+
+    /// The component of the splitter at each cell.
+    component: Vec<Option<usize>>,
+
+    /// The cells energized once a beam is split by a
+    /// splitter in each component.
+    energized: Vec<Energized>,
+}
+
+impl<'a> BeamGraph<'a> {
+    fn new(grid: &'a [Vec<Space>]) -> Self {
+        let height = grid.len();
+        let width = grid[0].len();
+        let mut graph = Self {
+            grid,
+            width,
+            height,
+            component: vec![None; width * height],
+            energized: Vec::new(),
+        };
+
+        // Trace the beams out of each splitter, numbering
+        // them by cell so they can be found again...
+        let mut splitters = Graph::new_directed();
+        let mut splitter = vec![None; width * height];
+        let mut outputs = Vec::new();
+        for (y, row) in grid.iter().enumerate() {
+            for (x, s) in row.iter().enumerate() {
+                let dir = match s {
+                    Space::SplitLR => Direction::Up,
+                    Space::SplitUD => Direction::Left,
+                    _ => continue,
+                };
+                let id = splitters.add_node(&(y * width + x).to_string());
+                splitter[y * width + x] = Some(id);
+                let segments = Beam::new(x, y, dir)
+                    .step(s, width, height)
+                    .into_iter()
+                    .map(|b| graph.trace(b))
+                    .collect::<Vec<_>>();
+                outputs.push((y * width + x, segments));
+                debug_assert_eq!(id as usize, outputs.len() - 1);
+            }
+        }
+        for (id, (_, segments)) in outputs.iter().enumerate() {
+            for next in segments.iter().filter_map(|seg| seg.splitter) {
+                if let Some(next) = splitter[next] {
+                    splitters.add_edge_ids(id as u32, next);
+                }
+            }
+        }
+
+        // Components come out with the ones they lead to
+        // first, so each one can build on those...
+        let components = splitters.strongly_connected_components();
+        for (c, members) in components.into_iter().enumerate() {
+            let mut energized = Energized::new(width * height);
+            for &id in &members {
+                let (cell, segments) = &outputs[id as usize];
+                energized.insert(*cell);
+                graph.component[*cell] = Some(c);
+                for seg in segments {
+                    for &cell in &seg.cells {
+                        energized.insert(cell);
+                    }
+                    let next = seg.splitter.and_then(|s| graph.component[s]);
+                    if let Some(next) = next.filter(|&n| n != c) {
+                        energized.union_with(&graph.energized[next]);
+                    }
+                }
+            }
+            graph.energized.push(energized);
+        }
+        graph
+    }
+
+    /// Follows a beam until it leaves the grid, loops or is
+    /// split.
+    fn trace(&self, start: Beam) -> Segment {
+        let mut cells = Vec::new();
+        let mut seen = HashSet::new();
+        let mut beam = start;
+        loop {
+            if !seen.insert(beam.clone()) {
+                return Segment { cells, splitter: None };
+            }
+            let cell = beam.pos.y * self.width + beam.pos.x;
+            cells.push(cell);
+            let s = &self.grid[beam.pos.y][beam.pos.x];
+            if splits(s, &beam.dir) {
+                return Segment { cells, splitter: Some(cell) };
+            }
+            match beam.step(s, self.width, self.height).pop() {
+                Some(next) => beam = next,
+                None => return Segment { cells, splitter: None },
+            }
+        }
+    }
+
+    /// Counts the cells energized by a beam starting at `start`.
+    fn count(&self, start: Beam) -> usize {
+        let seg = self.trace(start);
+        let mut energized = match seg.splitter.and_then(|s| self.component[s]) {
+            Some(c) => self.energized[c].clone(),
+            None => Energized::new(self.width * self.height),
+        };
+        for cell in seg.cells {
+            energized.insert(cell);
+        }
+        energized.len()
+    }
+
+    /// Every beam coming in from the edge of the grid.
+    fn edge_starts(&self) -> Vec<Beam> {
+        let (w, h) = (self.width, self.height);
+        let rows = (0..h).flat_map(|i| {
+            [Beam::new(0, i, Direction::Right), Beam::new(w - 1, i, Direction::Left)]
+        });
+        let cols = (0..w).flat_map(|i| {
+            [Beam::new(i, 0, Direction::Down), Beam::new(i, h - 1, Direction::Up)]
+        });
+        rows.chain(cols).collect()
+    }
+
+    /// Finds the most cells any edge beam energizes,
+    /// splitting the edge between `threads` threads.
+    fn best(&self, threads: usize) -> usize {
+        let starts = self.edge_starts();
+        let chunk = starts.len().div_ceil(threads.max(1)).max(1);
+        thread::scope(|scope| {
+            let handles = starts
+                .chunks(chunk)
+                .map(|starts| {
+                    scope.spawn(move || {
+                        starts.iter().map(|b| self.count(b.clone())).max().unwrap_or(0)
+                    })
+                })
+                .collect::<Vec<_>>();
+            // (Passing on any worker's panic, rather than
+            // counting it as nothing)...
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .max()
+                .unwrap_or(0)
+        })
+    }
+}
+
 fn main() -> Result<()> {
     // Parse the input as a grid of spaces...
     let input = load_input_lines_by_name(file!())?;
-    // let input = vec![
-    //     ".|...\\....".to_string(),
-    //     "|.-.\\.....".to_string(),
-    //     ".....|-...".to_string(),
-    //     "........|.".to_string(),
-    //     "..........".to_string(),
-    //     ".........\\".to_string(),
-    //     "..../.\\\\..".to_string(),
-    //     ".-.-/..|..".to_string(),
-    //     ".|....-|.\\".to_string(),
-    //     "..//.|....".to_string(),
-    // ];
     let grid = input
         .iter()
         .map(|line| line
             .chars()
-            .map(|c| Space::try_from(c))
+            .map(Space::try_from)
             .collect::<Result<Vec<Space>>>()
         )
         .collect::<Result<Vec<_>>>()?;
 
-    // Get the grid size...
-    let height = grid.len();
-    let width = grid[0].len();
-
-    // Track the best...
-    let mut best = 0;
-
-    // Loop along the left/right edges...
-    for i in 0..height {
-        // Try the left side...
-        let count = get_count(&grid, Beam::new(0, i, Direction::Right));
-        if count > best {
-            best = count;
-        }
-        
-        // Try the right side...
-        let count = get_count(&grid, Beam::new(width-1, i, Direction::Left));
-        if count > best {
-            best = count;
-        }
-    }
-    
-    // Loop along the top/bottom edges...
-    for i in 0..width {
-        // Try the left side...
-        let count = get_count(&grid, Beam::new(i, 0, Direction::Down));
-        if count > best {
-            best = count;
-        }
-        
-        // Try the right side...
-        let count = get_count(&grid, Beam::new(i, height-1, Direction::Up));
-        if count > best {
-            best = count;
-        }
-    }
+    // Precompute the beams between splitters, then try
+    // every edge in parallel...
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let best = BeamGraph::new(&grid).best(threads);
 
     // How many spots were energized?
-    println!("The best energy count was {}", best);
+    print_answer(best);
 
     Ok(())
 }
@@ -454,5 +623,44 @@ mod tests {
             );
         }
     }
-}
 
+    fn example() -> Vec<Vec<Space>> {
+        let input = [
+            ".|...\\....",
+            "|.-.\\.....",
+            ".....|-...",
+            "........|.",
+            "..........",
+            ".........\\",
+            "..../.\\\\..",
+            ".-.-/..|..",
+            ".|....-|.\\",
+            "..//.|....",
+        ];
+        input
+            .iter()
+            .map(|line| line.chars().map(|c| Space::try_from(c).unwrap()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_example() {
+        let grid = example();
+        let graph = BeamGraph::new(&grid);
+        assert_eq!(graph.count(Beam::new(0, 0, Direction::Right)), 46);
+        assert_eq!(graph.count(Beam::new(3, 0, Direction::Down)), 51);
+        assert_eq!(graph.best(1), 51);
+        assert_eq!(graph.best(4), 51);
+    }
+
+    #[test]
+    fn test_matches_simulation() {
+        // Every edge start should energize exactly what a
+        // full simulation does...
+        let grid = example();
+        let graph = BeamGraph::new(&grid);
+        for start in graph.edge_starts() {
+            assert_eq!(graph.count(start.clone()), get_count(&grid, start));
+        }
+    }
+}
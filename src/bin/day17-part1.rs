@@ -2,7 +2,9 @@
 #![allow(dead_code)]
 
 use anyhow::{anyhow, Result};
-use aoc23::load_input_lines_by_name;
+use aoc23::dot::{Dot, DOT_FLAG};
+use aoc23::grid::{arrow, Color, Grid};
+use aoc23::{has_flag, load_input_lines_by_name, print_answer};
 use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -367,9 +369,29 @@ fn main() -> Result<()> {
     }
 
     if let Some(p) = best_route {
-        println!("Best route cost: {}", p.cost);
+
+        // Optionally write out the grid with the route
+        // highlighted...
+        if has_flag(DOT_FLAG) {
+            let route = p
+                .points
+                .iter()
+                .map(|pt| Dot::cell_id(pt.y, pt.x))
+                .collect::<Vec<_>>();
+            Dot::grid(&grid, |d| d.to_string())
+                .highlight_path(&route)
+                .write_if_requested(file!())?;
+        }
+
+        // Optionally draw the grid with arrows along the route...
+        let cells = p.points.iter().map(|pt| (pt.y, pt.x)).collect::<Vec<_>>();
+        Grid::from_lines(&input)
+            .overlay_chars(cells.windows(2).map(|w| (w[1], arrow(w[0], w[1]))), Some(Color::Red))
+            .print_if_requested();
+
+        print_answer(p.cost);
     } else {
-        println!("No route found");
+        return Err(anyhow!("No route found"));
     }
 
     Ok(())
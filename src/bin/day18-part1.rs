@@ -1,5 +1,8 @@
 use anyhow::Result;
-use aoc23::load_input_lines_by_name;
+use aoc23::error::ParseError;
+use aoc23::parse::{self, Parser};
+use aoc23::parse_input_lines_by_name;
+use std::fmt;
 
 #[derive(Debug)]
 struct Color {
@@ -13,14 +16,24 @@ impl Color {
         Self { red, green, blue }
     }
 
-    fn parse_hex(s: &str) -> Result<Self> {
-        let red = u8::from_str_radix(&s[1..3], 16)
-            .map_err(anyhow::Error::msg)?;
-        let green = u8::from_str_radix(&s[3..5], 16)
-            .map_err(anyhow::Error::msg)?;
-        let blue = u8::from_str_radix(&s[5..7], 16)
-            .map_err(anyhow::Error::msg)?;
-        Ok(Self::new(red, green, blue))
+    /// Parses a color like `(#70c710)`.
+    fn parse_from(p: &mut Parser) -> Result<Self, ParseError> {
+        p.literal("(#")?;
+        let err = p.error("expected a color (6 hex digits)");
+        let hex = p.take_while(|c| c.is_ascii_hexdigit());
+        if hex.len() != 6 {
+            return Err(err);
+        }
+        let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| err.clone());
+        let color = Self::new(byte(0)?, byte(2)?, byte(4)?);
+        p.literal(")")?;
+        Ok(color)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(#{:02x}{:02x}{:02x})", self.red, self.green, self.blue)
     }
 }
 
@@ -32,50 +45,99 @@ enum Direction {
     Right,
 }
 
+impl Direction {
+    fn parse_from(p: &mut Parser) -> Result<Self, ParseError> {
+        let err = p.error("expected a direction (U, D, L or R)");
+        match p.take_while(|c| c.is_ascii_alphabetic()) {
+            "U" => Ok(Direction::Up),
+            "D" => Ok(Direction::Down),
+            "L" => Ok(Direction::Left),
+            "R" => Ok(Direction::Right),
+            _ => Err(err),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let c = match self {
+            Direction::Up => "U",
+            Direction::Down => "D",
+            Direction::Left => "L",
+            Direction::Right => "R",
+        };
+        write!(f, "{}", c)
+    }
+}
+
 #[derive(Debug)]
 struct Instruction {
     direction: Direction,
     distance: i32,
-    color: String,
+    color: Color,
 }
 
 impl Instruction {
-    fn new(direction: Direction, distance: i32, color: String) -> Self {
+    fn new(direction: Direction, distance: i32, color: Color) -> Self {
         Self { direction, distance, color }
     }
 
+    /// Parses a line like `R 6 (#70c710)`.
     fn parse(s: &str) -> Result<Self> {
-        let mut parts = s.split_whitespace();
-        let direction = parts.next().ok_or_else(|| anyhow::Error::msg("missing direction"))?;
-        let distance = parts.next().ok_or_else(|| anyhow::Error::msg("missing distance"))?;
-        let color = parts.next().ok_or_else(|| anyhow::Error::msg("missing color"))?;
-        Ok(Self::new(
-            direction.chars().next().ok_or_else(|| anyhow::Error::msg("missing direction"))?,
-            distance.parse::<i32>().map_err(anyhow::Error::msg)?,
-            color.to_string(),
-        ))
+        let instruction = parse::line(s, |p| {
+            let direction = Direction::parse_from(p)?;
+            p.literal(" ")?;
+            let distance = p.uint::<i32>()?;
+            p.literal(" ")?;
+            let color = Color::parse_from(p)?;
+            Ok(Self::new(direction, distance, color))
+        })?;
+        Ok(instruction)
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {}", self.direction, self.distance, self.color)
     }
 }
 
 fn main() -> Result<()> {
-    // let _input = load_input_lines_by_name(file!())?;
-    let input = vec![
-        "R 6 (#70c710)".to_string(),
-        "D 5 (#0dc571)".to_string(),
-        "L 2 (#5713f0)".to_string(),
-        "D 2 (#d2c081)".to_string(),
-        "R 2 (#59c680)".to_string(),
-        "D 2 (#411b91)".to_string(),
-        "L 5 (#8ceee2)".to_string(),
-        "U 2 (#caa173)".to_string(),
-        "L 1 (#1b58a2)".to_string(),
-        "U 2 (#caa171)".to_string(),
-        "R 2 (#7807d2)".to_string(),
-        "U 3 (#a77fa3)".to_string(),
-        "L 2 (#015232)".to_string(),
-        "U 2 (#7a21e3)".to_string(),
-    ];
+    let instructions = parse_input_lines_by_name(file!(), Instruction::parse)?;
+    println!("Parsed {} instructions", instructions.len());
 
     Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc23::parse_lines;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let input = vec![
+            "R 6 (#70c710)",
+            "D 5 (#0dc571)",
+            "L 2 (#5713f0)",
+            "U 2 (#7a21e3)",
+        ];
+        let instructions = parse_lines(&input, Instruction::parse)?;
+        let shown = instructions.iter().map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(shown, input);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |s| {
+            let e = Instruction::parse(s).unwrap_err().downcast::<ParseError>().unwrap();
+            (e.column, e.message)
+        };
+        assert_eq!(error("X 6 (#70c710)").0, 1);
+        assert_eq!(error("R x (#70c710)").0, 3);
+        assert_eq!(error("R 6 junk").0, 5);
+        assert_eq!(error("R 6 (#70c71)").1, "expected a color (6 hex digits)");
+        assert_eq!(error("R 6 (#70c710) x"), (15, "unexpected text".to_string()));
+    }
+}
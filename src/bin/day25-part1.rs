@@ -0,0 +1,113 @@
+use anyhow::{anyhow, Result};
+use aoc23::graph::{min_cut, Graph};
+use aoc23::{load_input_lines_by_name, print_answer};
+
+/// Parses a wiring line like `jqt: rhn xhk nvd` into
+/// the component name and the components it connects to.
+fn parse_line(line: &str) -> Result<(&str, Vec<&str>)> {
+    let (name, rest) = line
+        .split_once(':')
+        .ok_or(anyhow!("Invalid line: {}", line))?;
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(anyhow!("Missing component name in line: {}", line));
+    }
+    let connections = rest.split_whitespace().collect::<Vec<_>>();
+    if connections.is_empty() {
+        return Err(anyhow!("No connections in line: {}", line));
+    }
+    Ok((name, connections))
+}
+
+/// Builds the wiring graph from the input lines.
+fn parse_graph(lines: &[String]) -> Result<Graph> {
+    let mut g = Graph::new();
+    for line in lines.iter().filter(|l| !l.trim().is_empty()) {
+        let (name, connections) = parse_line(line)?;
+        for other in connections {
+            g.add_edge(name, other);
+        }
+    }
+    Ok(g)
+}
+
+fn main() -> Result<()> {
+    // Load the input and build the graph...
+    let lines = load_input_lines_by_name(file!())?;
+    let g = parse_graph(&lines)?;
+    println!(
+        "Found {} components and {} wires",
+        g.node_count(),
+        g.edge_count()
+    );
+
+    // Find the minimum cut...
+    let cut = min_cut(&g)?;
+    for (a, b) in &cut.edges {
+        println!("Cut wire {}/{}", g.label(*a), g.label(*b));
+    }
+    println!("Group sizes: {} x {}", cut.sizes.0, cut.sizes.1);
+    print_answer(cut.product());
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Vec<String> {
+        vec![
+            "jqt: rhn xhk nvd",
+            "rsh: frs pzl lsr",
+            "xhk: hfx",
+            "cmg: qnr nvd lhk bvb",
+            "rhn: xhk bvb hfx",
+            "bvb: xhk hfx",
+            "pzl: lsr hfx nvd",
+            "qnr: nvd",
+            "ntq: jqt hfx bvb xhk",
+            "nvd: lhk",
+            "lsr: lhk",
+            "rzs: qnr cmg lsr rsh",
+            "frs: qnr lhk lsr",
+        ]
+        .into_iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    #[test]
+    fn test_parse_line() -> Result<()> {
+        let (name, connections) = parse_line("jqt: rhn xhk nvd")?;
+        assert_eq!(name, "jqt");
+        assert_eq!(connections, vec!["rhn", "xhk", "nvd"]);
+        assert!(parse_line("jqt rhn").is_err());
+        assert!(parse_line("jqt:").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_example() -> Result<()> {
+        let g = parse_graph(&example())?;
+        assert_eq!(g.node_count(), 15);
+        assert_eq!(g.edge_count(), 33);
+
+        let cut = min_cut(&g)?;
+        assert_eq!(cut.weight, 3);
+        assert_eq!(cut.product(), 54);
+
+        let mut wires = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                let mut w = [g.label(a), g.label(b)];
+                w.sort();
+                w.join("/")
+            })
+            .collect::<Vec<_>>();
+        wires.sort();
+        assert_eq!(wires, vec!["bvb/cmg", "hfx/pzl", "jqt/nvd"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,398 @@
+use crate::error::{in_context, ParseError};
+use crate::parse_lines;
+use anyhow::{anyhow, Result};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// The rules for a game of Camel Cards (day 7): which cards
+/// exist and how they're ranked, which card (if any) is
+/// wild, and how many cards are in a hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    /// The cards from weakest to strongest.
+    order: Vec<char>,
+    wild: Option<char>,
+    hand_size: usize,
+}
+
+impl Rules {
+    /// Creates a rule set. `order` lists the cards from
+    /// weakest to strongest, and the wild card (if any) has
+    /// to be one of them.
+    pub fn new(order: &str, wild: Option<char>, hand_size: usize) -> Result<Self> {
+        let order = order.chars().collect::<Vec<_>>();
+        for (i, c) in order.iter().enumerate() {
+            if order[..i].contains(c) {
+                return Err(anyhow!("Card '{}' appears twice in the card order", c));
+            }
+        }
+        if let Some(w) = wild {
+            if !order.contains(&w) {
+                return Err(anyhow!("Wild card '{}' isn't in the card order", w));
+            }
+        }
+        if hand_size == 0 {
+            return Err(anyhow!("Hands need at least one card"));
+        }
+        Ok(Self {
+            order,
+            wild,
+            hand_size,
+        })
+    }
+
+    /// The part 1 rules: no wild cards, jacks between tens
+    /// and queens.
+    pub fn standard() -> Self {
+        Self::new("23456789TJQKA", None, 5).expect("standard rules are valid")
+    }
+
+    /// The part 2 rules: jacks are jokers, which are wild
+    /// but the weakest card on their own.
+    pub fn jokers() -> Self {
+        Self::new("J23456789TQKA", Some('J'), 5).expect("joker rules are valid")
+    }
+
+    pub fn wild(&self) -> Option<char> {
+        self.wild
+    }
+
+    pub fn hand_size(&self) -> usize {
+        self.hand_size
+    }
+
+    /// Returns the card's strength (higher is stronger).
+    pub fn strength(&self, card: char) -> Option<usize> {
+        self.order.iter().position(|&c| c == card)
+    }
+
+    /// Parses and evaluates a hand like `T55J5`.
+    pub fn hand(&self, cards: &str) -> Result<Hand> {
+        let chars = cards.chars().collect::<Vec<_>>();
+        if chars.len() != self.hand_size {
+            let msg = format!("expected {} cards, found {}", self.hand_size, chars.len());
+            return Err(ParseError::new(cards, msg).into());
+        }
+        let strengths = chars
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                self.strength(c)
+                    .ok_or_else(|| ParseError::at(cards, i + 1, format!("invalid card '{}'", c)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Count each (non-wild) card...
+        let mut counts = vec![0; self.order.len()];
+        let mut wild = 0;
+        for &c in &chars {
+            if Some(c) == self.wild {
+                wild += 1;
+            } else if let Some(s) = self.strength(c) {
+                counts[s] += 1;
+            }
+        }
+
+        // The wild cards are always best spent joining the
+        // biggest group...
+        let mut shape = counts.into_iter().filter(|&n| n > 0).collect::<Vec<_>>();
+        shape.sort_unstable_by(|a, b| b.cmp(a));
+        match shape.first_mut() {
+            Some(n) => *n += wild,
+            None => shape.push(wild),
+        }
+
+        Ok(Hand {
+            cards: cards.to_string(),
+            shape,
+            strengths,
+            wild,
+        })
+    }
+}
+
+/// The type of a hand. With hand sizes other than five
+/// this is only a label; hands are ordered by their group
+/// sizes (see [`Hand::shape`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Kind {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    FullHouse,
+    FourOfAKind,
+    FiveOfAKind,
+}
+
+impl Kind {
+    fn from_shape(shape: &[usize]) -> Self {
+        match (shape[0], shape.get(1).copied().unwrap_or(0)) {
+            (n, _) if n >= 5 => Self::FiveOfAKind,
+            (4, _) => Self::FourOfAKind,
+            (3, m) if m >= 2 => Self::FullHouse,
+            (3, _) => Self::ThreeOfAKind,
+            (2, 2) => Self::TwoPair,
+            (2, _) => Self::OnePair,
+            _ => Self::HighCard,
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::HighCard => "high card",
+            Self::OnePair => "one pair",
+            Self::TwoPair => "two pair",
+            Self::ThreeOfAKind => "three of a kind",
+            Self::FullHouse => "full house",
+            Self::FourOfAKind => "four of a kind",
+            Self::FiveOfAKind => "five of a kind",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// An evaluated hand.
+///
+/// Hands are ordered by type first and then card by card.
+/// Only compare hands made with the same [`Rules`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Hand {
+    cards: String,
+
+    /// Group sizes from largest to smallest, with the wild
+    /// cards added to the largest group.
+    shape: Vec<usize>,
+
+    /// The strength of each card, in the order dealt.
+    strengths: Vec<usize>,
+
+    wild: usize,
+}
+
+impl Hand {
+    pub fn cards(&self) -> &str {
+        &self.cards
+    }
+
+    /// The hand's group sizes, largest first (eg `[3, 2]`
+    /// for a full house).
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    pub fn kind(&self) -> Kind {
+        Kind::from_shape(&self.shape)
+    }
+
+    /// Describes why `self` and `other` are ordered the way
+    /// they are.
+    pub fn explain(&self, other: &Self) -> String {
+        let describe = |h: &Hand| match h.wild {
+            0 => format!("{} ({})", h.cards, h.kind()),
+            n => format!("{} ({}, {} wild)", h.cards, h.kind(), n),
+        };
+        let (hi, lo) = match self.cmp(other) {
+            Ordering::Equal => return format!("{} ties {}", describe(self), describe(other)),
+            Ordering::Greater => (self, other),
+            Ordering::Less => (other, self),
+        };
+        if hi.shape != lo.shape {
+            return format!("{} beats {} on hand type", describe(hi), describe(lo));
+        }
+
+        // Same type, so it comes down to the first card
+        // that differs...
+        let i = hi
+            .strengths
+            .iter()
+            .zip(&lo.strengths)
+            .position(|(a, b)| a != b)
+            .unwrap_or(0);
+        let card = |h: &Hand| h.cards.chars().nth(i).unwrap_or('?');
+        format!(
+            "{} beats {} on card {}: {} beats {}",
+            describe(hi),
+            describe(lo),
+            i + 1,
+            card(hi),
+            card(lo)
+        )
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.shape
+            .cmp(&other.shape)
+            .then_with(|| self.strengths.cmp(&other.strengths))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A list of hands and their bids.
+#[derive(Debug, Clone)]
+pub struct Game {
+    hands: Vec<(Hand, usize)>,
+}
+
+impl Game {
+    /// Parses lines like `32T3K 765` using `rules`.
+    pub fn parse<S: AsRef<str>>(rules: &Rules, lines: &[S]) -> Result<Self> {
+        let hands = parse_lines(lines, |line| Self::parse_line(rules, line))?;
+        Ok(Self { hands })
+    }
+
+    fn parse_line(rules: &Rules, line: &str) -> Result<(Hand, usize)> {
+        let (cards, bid) = line.split_once(' ').ok_or_else(|| {
+            ParseError::at(line, line.chars().count() + 1, "expected a hand and a bid")
+        })?;
+        let hand = rules.hand(cards).map_err(|e| in_context(e, line, cards))?;
+        let bid = bid.trim();
+        let bid = bid
+            .parse::<usize>()
+            .map_err(|_| ParseError::within(line, bid, "invalid bid"))?;
+        Ok((hand, bid))
+    }
+
+    /// Returns the hands from weakest to strongest.
+    pub fn ranked(&self) -> Vec<&(Hand, usize)> {
+        let mut ranked = self.hands.iter().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| a.0.cmp(&b.0));
+        ranked
+    }
+
+    /// Each hand's bid times its rank, summed.
+    pub fn winnings(&self) -> usize {
+        self.ranked()
+            .iter()
+            .enumerate()
+            .map(|(i, (_, bid))| (i + 1) * bid)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 5] = [
+        "32T3K 765",
+        "T55J5 684",
+        "KK677 28",
+        "KTJJT 220",
+        "QQQJA 483",
+    ];
+
+    #[test]
+    fn test_rules() {
+        let rules = Rules::standard();
+        assert!(rules.strength('A') > rules.strength('K'));
+        assert!(rules.strength('Q') > rules.strength('J'));
+        assert!(rules.strength('J') > rules.strength('T'));
+        assert_eq!(rules.strength('x'), None);
+
+        let rules = Rules::jokers();
+        assert!(rules.strength('J') < rules.strength('2'));
+        assert!(Rules::new("AA", None, 5).is_err());
+        assert!(Rules::new("AK", Some('J'), 5).is_err());
+        assert!(rules.hand("AAAA").is_err());
+        assert!(rules.hand("AAAAx").is_err());
+    }
+
+    #[test]
+    fn test_kinds() -> Result<()> {
+        let rules = Rules::standard();
+        let kind = |cards| rules.hand(cards).map(|h| h.kind());
+        assert_eq!(kind("AAAAA")?, Kind::FiveOfAKind);
+        assert_eq!(kind("AAKAA")?, Kind::FourOfAKind);
+        assert_eq!(kind("KAAAK")?, Kind::FullHouse);
+        assert_eq!(kind("QAAKA")?, Kind::ThreeOfAKind);
+        assert_eq!(kind("QAAKK")?, Kind::TwoPair);
+        assert_eq!(kind("QAAKJ")?, Kind::OnePair);
+        assert_eq!(kind("QAKJT")?, Kind::HighCard);
+        assert!(Kind::FiveOfAKind > Kind::FourOfAKind);
+        assert!(Kind::OnePair > Kind::HighCard);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wild_cards() -> Result<()> {
+        let rules = Rules::jokers();
+        let kind = |cards| rules.hand(cards).map(|h| h.kind());
+        assert_eq!(kind("T55J5")?, Kind::FourOfAKind);
+        assert_eq!(kind("KTJJT")?, Kind::FourOfAKind);
+        assert_eq!(kind("JJJJJ")?, Kind::FiveOfAKind);
+        assert_eq!(kind("JJJJ2")?, Kind::FiveOfAKind);
+        assert_eq!(kind("2233J")?, Kind::FullHouse);
+        assert_eq!(kind("2345J")?, Kind::OnePair);
+
+        // A joker on its own is the weakest card...
+        assert!(rules.hand("JKKK2")? < rules.hand("QQQQ2")?);
+
+        // Any hand size works...
+        let rules = Rules::new("J23", Some('J'), 7)?;
+        assert_eq!(rules.hand("JJ22333")?.shape(), &[5, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_order() -> Result<()> {
+        let rules = Rules::standard();
+        let cases = [
+            ("32T3K", "T55J5", Ordering::Less),
+            ("T55J5", "KK677", Ordering::Greater),
+            ("KK677", "KTJJT", Ordering::Greater),
+            ("33332", "2AAAA", Ordering::Greater),
+            ("77888", "77788", Ordering::Greater),
+        ];
+        for (left, right, ord) in cases {
+            assert_eq!(
+                rules.hand(left)?.cmp(&rules.hand(right)?),
+                ord,
+                "{} vs {}",
+                left,
+                right
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain() -> Result<()> {
+        let rules = Rules::jokers();
+        let a = rules.hand("KTJJT")?;
+        let b = rules.hand("QQQJA")?;
+        let c = rules.hand("KK677")?;
+        assert_eq!(
+            b.explain(&a),
+            "KTJJT (four of a kind, 2 wild) beats QQQJA (four of a kind, 1 wild) on card 1: K beats Q"
+        );
+        assert_eq!(
+            c.explain(&a),
+            "KTJJT (four of a kind, 2 wild) beats KK677 (two pair) on hand type"
+        );
+        assert_eq!(c.explain(&c), "KK677 (two pair) ties KK677 (two pair)");
+        Ok(())
+    }
+
+    #[test]
+    fn test_winnings() -> Result<()> {
+        assert_eq!(Game::parse(&Rules::standard(), &EXAMPLE)?.winnings(), 6440);
+        assert_eq!(Game::parse(&Rules::jokers(), &EXAMPLE)?.winnings(), 5905);
+        assert!(Game::parse(&Rules::standard(), &["32T3K"]).is_err());
+
+        // Errors point at the bad card...
+        let e = Game::parse(&Rules::standard(), &["32T3K 765", "T5X5J 684"]).unwrap_err();
+        let e = e.downcast::<ParseError>().unwrap();
+        assert_eq!((e.line, e.column, e.width), (Some(2), 3, 1));
+        Ok(())
+    }
+}
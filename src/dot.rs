@@ -0,0 +1,296 @@
+use crate::{has_flag, out_path};
+use anyhow::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// The command line flag that asks a solution to
+/// write out its `.dot` file.
+pub const DOT_FLAG: &str = "--dot";
+
+#[derive(Debug, Clone)]
+struct DotNode {
+    id: String,
+    label: String,
+    pos: Option<(usize, usize)>,
+    highlighted: bool,
+}
+
+#[derive(Debug, Clone)]
+struct DotEdge {
+    a: String,
+    b: String,
+    label: Option<String>,
+    highlighted: bool,
+}
+
+/// Quotes `s` as a DOT string. Only `"` and `\` need
+/// escaping (unlike Rust's `{:?}`, which would also escape
+/// things DOT doesn't understand, like `\u{1b}`).
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        if c == '"' || c == '\\' {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out.push('"');
+    out
+}
+
+/// A builder for Graphviz DOT output.
+///
+/// Nodes can be given a grid position (row, column), in
+/// which case the output uses the `neato` layout and pins
+/// them in place so grid puzzles keep their shape. Nodes
+/// and edges can be highlighted to show things like a
+/// found loop or a best route.
+#[derive(Debug, Clone, Default)]
+pub struct Dot {
+    directed: bool,
+    nodes: Vec<DotNode>,
+    edges: Vec<DotEdge>,
+
+    /// Where the nodes with each id are in `nodes`, and the
+    /// edges from `a` to `b` are in `edges`, so highlighting
+    /// a path doesn't have to search for them.
+    node_index: HashMap<String, Vec<usize>>,
+    edge_index: HashMap<(String, String), Vec<usize>>,
+}
+
+impl Dot {
+    pub fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            ..Self::default()
+        }
+    }
+
+    /// Builds an undirected grid graph with a node for every
+    /// cell, labeled with `label`, and an edge between each
+    /// pair of orthogonal neighbors.
+    ///
+    /// Node ids are `"r{row}c{col}"` (see `Dot::cell_id`).
+    pub fn grid<T>(cells: &[Vec<T>], label: impl Fn(&T) -> String) -> Self {
+        let mut dot = Self::new(false);
+        for (row, line) in cells.iter().enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                dot.grid_node(&Self::cell_id(row, col), &label(cell), row, col);
+                if col > 0 {
+                    dot.edge(&Self::cell_id(row, col - 1), &Self::cell_id(row, col), None);
+                }
+                if row > 0 && col < cells[row - 1].len() {
+                    dot.edge(&Self::cell_id(row - 1, col), &Self::cell_id(row, col), None);
+                }
+            }
+        }
+        dot
+    }
+
+    /// The node id used for the cell at `row`, `col`.
+    pub fn cell_id(row: usize, col: usize) -> String {
+        format!("r{}c{}", row, col)
+    }
+
+    pub fn node(&mut self, id: &str, label: &str) -> &mut Self {
+        self.node_index
+            .entry(id.to_string())
+            .or_default()
+            .push(self.nodes.len());
+        self.nodes.push(DotNode {
+            id: id.to_string(),
+            label: label.to_string(),
+            pos: None,
+            highlighted: false,
+        });
+        self
+    }
+
+    /// Adds a node pinned at the given grid position.
+    pub fn grid_node(&mut self, id: &str, label: &str, row: usize, col: usize) -> &mut Self {
+        self.node(id, label);
+        if let Some(node) = self.nodes.last_mut() {
+            node.pos = Some((row, col));
+        }
+        self
+    }
+
+    pub fn edge(&mut self, a: &str, b: &str, label: Option<&str>) -> &mut Self {
+        self.edge_index
+            .entry((a.to_string(), b.to_string()))
+            .or_default()
+            .push(self.edges.len());
+        self.edges.push(DotEdge {
+            a: a.to_string(),
+            b: b.to_string(),
+            label: label.map(|l| l.to_string()),
+            highlighted: false,
+        });
+        self
+    }
+
+    pub fn highlight_node(&mut self, id: &str) -> &mut Self {
+        for &i in self.node_index.get(id).into_iter().flatten() {
+            self.nodes[i].highlighted = true;
+        }
+        self
+    }
+
+    /// The first edge from `a` to `b` (or either way round,
+    /// if the graph is undirected) that isn't highlighted.
+    fn unhighlighted_edge(&self, a: &str, b: &str) -> Option<usize> {
+        let edges = |a: &str, b: &str| {
+            self.edge_index
+                .get(&(a.to_string(), b.to_string()))
+                .into_iter()
+                .flatten()
+                .copied()
+        };
+        edges(a, b)
+            .chain(edges(b, a).filter(|_| !self.directed))
+            .filter(|&i| !self.edges[i].highlighted)
+            .min()
+    }
+
+    /// Highlights every node along `path` and, for each
+    /// consecutive pair, the first matching edge between them.
+    pub fn highlight_path<S: AsRef<str>>(&mut self, path: &[S]) -> &mut Self {
+        for id in path {
+            self.highlight_node(id.as_ref());
+        }
+        for pair in path.windows(2) {
+            if let Some(i) = self.unhighlighted_edge(pair[0].as_ref(), pair[1].as_ref()) {
+                self.edges[i].highlighted = true;
+            }
+        }
+        self
+    }
+
+    /// Renders the graph as DOT source.
+    pub fn render(&self) -> String {
+        let (kind, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let mut out = format!("{} {{\n", kind);
+        if self.nodes.iter().any(|n| n.pos.is_some()) {
+            out.push_str("    layout=neato;\n");
+            out.push_str("    node [shape=box];\n");
+        }
+        for node in &self.nodes {
+            let mut attrs = vec![format!("label={}", quote(&node.label))];
+            if let Some((row, col)) = node.pos {
+                attrs.push(format!("pos=\"{},-{}!\"", col, row));
+            }
+            if node.highlighted {
+                attrs.push("color=red".to_string());
+                attrs.push("style=bold".to_string());
+            }
+            let id = quote(&node.id);
+            out.push_str(&format!("    {} [{}];\n", id, attrs.join(", ")));
+        }
+        for edge in &self.edges {
+            let mut attrs = Vec::new();
+            if let Some(label) = &edge.label {
+                attrs.push(format!("label={}", quote(label)));
+            }
+            if edge.highlighted {
+                attrs.push("color=red".to_string());
+                attrs.push("penwidth=2".to_string());
+            }
+            let attrs = if attrs.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", attrs.join(", "))
+            };
+            let (a, b) = (quote(&edge.a), quote(&edge.b));
+            out.push_str(&format!("    {} {} {}{};\n", a, arrow, b, attrs));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Writes the graph to `out/<name>.dot`, where `<name>`
+    /// is the file stem of `name` (eg `file!()`).
+    pub fn write(&self, name: &str) -> Result<PathBuf> {
+        let path = out_path(name, ".dot")?;
+        fs::write(&path, self.render())?;
+        Ok(path)
+    }
+
+    /// Writes the graph (see `Dot::write`) if the program
+    /// was run with the `--dot` flag.
+    pub fn write_if_requested(&self, name: &str) -> Result<()> {
+        if has_flag(DOT_FLAG) {
+            let path = self.write(name)?;
+            println!("Wrote {}", path.display());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut dot = Dot::new(true);
+        dot.node("a", "AAA")
+            .node("b", "BBB")
+            .edge("a", "b", Some("L"))
+            .edge("a", "b", Some("R"))
+            .highlight_path(&["a", "b"]);
+        assert_eq!(
+            dot.render(),
+            [
+                "digraph {",
+                "    \"a\" [label=\"AAA\", color=red, style=bold];",
+                "    \"b\" [label=\"BBB\", color=red, style=bold];",
+                "    \"a\" -> \"b\" [label=\"L\", color=red, penwidth=2];",
+                "    \"a\" -> \"b\" [label=\"R\"];",
+                "}",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_highlight_direction() {
+        // Directed edges only match the way round they go...
+        let mut dot = Dot::new(true);
+        dot.edge("a", "b", None).highlight_path(&["b", "a"]);
+        assert!(!dot.render().contains("color=red"));
+        let mut dot = Dot::new(false);
+        dot.edge("a", "b", None).highlight_path(&["b", "a"]);
+        assert!(dot
+            .render()
+            .contains("\"a\" -- \"b\" [color=red, penwidth=2]"));
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote("r1c2"), "\"r1c2\"");
+        assert_eq!(
+            quote("say \"hi\" \\ 'bye'"),
+            "\"say \\\"hi\\\" \\\\ 'bye'\""
+        );
+        assert_eq!(quote("\u{1b}[31m"), "\"\u{1b}[31m\"");
+    }
+
+    #[test]
+    fn test_grid() {
+        let cells = vec![vec![1, 2], vec![3, 4]];
+        let mut dot = Dot::grid(&cells, |c| c.to_string());
+        dot.highlight_path(&[Dot::cell_id(1, 1), Dot::cell_id(0, 1)]);
+        let out = dot.render();
+        assert!(out.starts_with("graph {\n    layout=neato;\n"));
+        assert!(out.contains("    \"r1c0\" [label=\"3\", pos=\"0,-1!\"];\n"));
+        assert!(out.contains("    \"r0c1\" -- \"r1c1\" [color=red, penwidth=2];\n"));
+        assert_eq!(out.matches(" -- ").count(), 4);
+    }
+}
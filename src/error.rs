@@ -0,0 +1,297 @@
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// An error parsing puzzle input, pointing at the part of
+/// the line that didn't make sense:
+///
+/// ```text
+/// day 2, line 3, column 9: expected a number
+///  3 | Game 3: x red
+///    |         ^
+/// ```
+///
+/// Parsers usually only know the text they were given, so
+/// the day and line number get filled in further up (see
+/// [`crate::parse_lines`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u8>,
+
+    /// The (1-based) line number.
+    pub line: Option<usize>,
+
+    /// The (1-based) column, in characters, of the start of
+    /// the problem and how many characters it covers.
+    pub column: usize,
+    pub width: usize,
+
+    /// The text (usually a whole line) that failed to parse.
+    pub text: String,
+
+    pub message: String,
+}
+
+impl ParseError {
+    /// An error about the whole of `text`.
+    pub fn new(text: &str, message: impl Into<String>) -> Self {
+        Self::span(text, 1, text.chars().count(), message)
+    }
+
+    /// An error about the character at `column` in `text`
+    /// (which may be one past the end, for things that are
+    /// missing).
+    pub fn at(text: &str, column: usize, message: impl Into<String>) -> Self {
+        Self::span(text, column, 1, message)
+    }
+
+    /// An error about `width` characters from `column`.
+    pub fn span(text: &str, column: usize, width: usize, message: impl Into<String>) -> Self {
+        Self {
+            day: None,
+            line: None,
+            column: column.max(1),
+            width: width.max(1),
+            text: text.to_string(),
+            message: message.into(),
+        }
+    }
+
+    /// An error about `part`, which is a slice of `text`
+    /// (or, failing that, the first place it appears).
+    pub fn within(text: &str, part: &str, message: impl Into<String>) -> Self {
+        match offset_of(text, part) {
+            Some(offset) => Self::span(
+                text,
+                text[..offset].chars().count() + 1,
+                part.chars().count(),
+                message,
+            ),
+            None => Self::new(text, message),
+        }
+    }
+
+    /// Re-anchors an error about `inner`, a slice of `outer`,
+    /// so that it points into `outer` instead.
+    pub fn in_context(mut self, outer: &str, inner: &str) -> Self {
+        if let Some(offset) = offset_of(outer, inner) {
+            self.column += outer[..offset].chars().count();
+            self.text = outer.to_string();
+        }
+        self
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+
+    pub fn with_day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+}
+
+/// Returns the byte offset of `part` in `text`, checking if
+/// it's literally a slice of `text` before searching for it.
+fn offset_of(text: &str, part: &str) -> Option<usize> {
+    let start = text.as_ptr() as usize;
+    let p = part.as_ptr() as usize;
+    if p >= start && p + part.len() <= start + text.len() {
+        Some(p - start)
+    } else {
+        text.find(part)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Where...
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "line {}, ", line)?;
+        }
+        writeln!(f, "column {}: {}", self.column, self.message)?;
+
+        // ...and what, with a caret under it...
+        let gutter = self.line.map(|l| l.to_string()).unwrap_or_default();
+        writeln!(f, " {} | {}", gutter, self.text)?;
+        write!(
+            f,
+            " {} | {}{}",
+            " ".repeat(gutter.len()),
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width)
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The ways loading and parsing a day's input can fail.
+///
+/// It's a `std::error::Error`, so binaries can still use `?`
+/// with `anyhow`, but callers that care can tell the cases
+/// apart.
+#[derive(Debug)]
+pub enum AocError {
+    /// There's no input file for the day.
+    MissingInput {
+        day: u8,
+        path: PathBuf,
+    },
+
+    /// The input file is there, but couldn't be read.
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+
+    /// A binary's name (eg `file!()`) doesn't say which
+    /// day it is (it should look like `dayNN-partM.rs`).
+    InvalidDay(String),
+
+    Parse(ParseError),
+
+    /// Anything else that went wrong while solving.
+    Solution(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl AocError {
+    /// An error reading `path` (the input for day `day`).
+    pub fn read(day: u8, path: PathBuf, source: io::Error) -> Self {
+        match source.kind() {
+            io::ErrorKind::NotFound => AocError::MissingInput { day, path },
+            _ => AocError::Io { path, source },
+        }
+    }
+
+    /// Adds the day to a parse error.
+    pub fn with_day(self, day: u8) -> Self {
+        match self {
+            AocError::Parse(e) => AocError::Parse(e.with_day(day)),
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for AocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AocError::MissingInput { day, path } => {
+                write!(
+                    f,
+                    "No input for day {} ({} is missing)",
+                    day,
+                    path.display()
+                )
+            }
+            AocError::Io { path, source } => {
+                write!(f, "Couldn't read {}: {}", path.display(), source)
+            }
+            AocError::InvalidDay(name) => {
+                write!(
+                    f,
+                    "Can't tell which day \"{}\" is (expected dayNN-partM.rs)",
+                    name
+                )
+            }
+            AocError::Parse(e) => e.fmt(f),
+            AocError::Solution(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for AocError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AocError::Io { source, .. } => Some(source),
+            AocError::Solution(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for AocError {
+    fn from(e: ParseError) -> Self {
+        AocError::Parse(e)
+    }
+}
+
+/// Anything a solution returns that isn't already a parse
+/// error becomes [`AocError::Solution`].
+impl From<anyhow::Error> for AocError {
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<ParseError>() {
+            Ok(e) => AocError::Parse(e),
+            Err(e) => AocError::Solution(e.into()),
+        }
+    }
+}
+
+/// Re-anchors `e` to `outer` if it's a [`ParseError`] about
+/// `inner` (see [`ParseError::in_context`]), for passing on
+/// errors from parsing part of a line.
+pub fn in_context(e: anyhow::Error, outer: &str, inner: &str) -> anyhow::Error {
+    match e.downcast::<ParseError>() {
+        Ok(e) => e.in_context(outer, inner).into(),
+        Err(e) => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let e = ParseError::at("Game 3: x red", 9, "expected a number")
+            .with_line(3)
+            .with_day(2);
+        assert_eq!(
+            e.to_string(),
+            "day 2, line 3, column 9: expected a number\n 3 | Game 3: x red\n   |         ^"
+        );
+
+        let e = ParseError::new("oops", "bad line");
+        assert_eq!(e.to_string(), "column 1: bad line\n  | oops\n  | ^^^^");
+    }
+
+    #[test]
+    fn test_aoc_error() {
+        let missing = io::Error::from(io::ErrorKind::NotFound);
+        let e = AocError::read(3, PathBuf::from("data/03.txt"), missing);
+        assert!(matches!(e, AocError::MissingInput { day: 3, .. }));
+        assert_eq!(e.to_string(), "No input for day 3 (data/03.txt is missing)");
+
+        // Parse errors keep their type through anyhow...
+        let e = AocError::from(anyhow::Error::new(ParseError::new("x", "bad")));
+        assert!(matches!(
+            e.with_day(4),
+            AocError::Parse(ParseError { day: Some(4), .. })
+        ));
+        let e = AocError::from(anyhow::anyhow!("no winner"));
+        assert!(matches!(&e, AocError::Solution(_)));
+        assert_eq!(e.to_string(), "no winner");
+    }
+
+    #[test]
+    fn test_within() {
+        let line = "Game 3: 3 blue, x red";
+        let sets = &line[8..];
+        let part = &sets[8..];
+        let e = ParseError::within(sets, part, "expected a number");
+        assert_eq!((e.column, e.width), (9, 5));
+
+        // Re-anchored to the whole line...
+        let e = e.in_context(line, sets);
+        assert_eq!((e.column, e.width), (17, 5));
+        assert_eq!(e.text, line);
+
+        // Not a slice, so it's found by searching...
+        let e = ParseError::within("a, b, c", "c", "no");
+        assert_eq!(e.column, 7);
+    }
+}
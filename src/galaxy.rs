@@ -0,0 +1,171 @@
+use anyhow::{anyhow, Context, Result};
+
+/// The galaxies in an image of the sky (day 11), as
+/// `(x, y)` positions before any expansion.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Galaxies {
+    points: Vec<(u64, u64)>,
+    empty_cols: Vec<u64>,
+    empty_rows: Vec<u64>,
+}
+
+impl Galaxies {
+    /// Finds the galaxies (`#`) and the empty rows and
+    /// columns in the image.
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Self {
+        let width = lines.iter().map(|l| l.as_ref().len()).max().unwrap_or(0);
+        let mut cols_used = vec![false; width];
+        let mut rows_used = vec![false; lines.len()];
+        let mut points = Vec::new();
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.as_ref().bytes().enumerate() {
+                if c == b'#' {
+                    points.push((x as u64, y as u64));
+                    cols_used[x] = true;
+                    rows_used[y] = true;
+                }
+            }
+        }
+
+        // (These come out sorted, which `expand` relies on)...
+        let empty = |used: Vec<bool>| {
+            used.iter()
+                .enumerate()
+                .filter(|(_, &u)| !u)
+                .map(|(i, _)| i as u64)
+                .collect::<Vec<_>>()
+        };
+        Self {
+            points,
+            empty_cols: empty(cols_used),
+            empty_rows: empty(rows_used),
+        }
+    }
+
+    pub fn points(&self) -> &[(u64, u64)] {
+        &self.points
+    }
+
+    /// Returns the galaxy positions after every empty row
+    /// and column grows to `factor` rows or columns, failing
+    /// if they don't fit in a `u64`.
+    pub fn expand(&self, factor: u64) -> Result<Vec<(u64, u64)>> {
+        let grow = factor.saturating_sub(1);
+        let shift = |empty: &[u64], n: u64| {
+            // The number of empty lines before `n`...
+            let before = empty.partition_point(|&e| e < n) as u64;
+            before
+                .checked_mul(grow)
+                .and_then(|g| g.checked_add(n))
+                .ok_or(anyhow!("Expanding by {} is too big", factor))
+        };
+        self.points
+            .iter()
+            .map(|&(x, y)| Ok((shift(&self.empty_cols, x)?, shift(&self.empty_rows, y)?)))
+            .collect()
+    }
+
+    /// The sum of the distances between every pair of
+    /// galaxies after expanding by `factor`.
+    pub fn distance_sum(&self, factor: u64) -> Result<u128> {
+        Ok(pairwise_distance_sum(&self.expand(factor)?))
+    }
+}
+
+/// Parses an expansion factor (eg from `--factor`). Each
+/// empty row or column becomes `factor` of them, so it has
+/// to be at least 1.
+pub fn parse_factor(s: &str) -> Result<u64> {
+    let factor = s.parse::<u64>().context("Invalid --factor")?;
+    if factor == 0 {
+        return Err(anyhow!("Invalid --factor (it has to be at least 1)"));
+    }
+    Ok(factor)
+}
+
+/// Sums the Manhattan distances between every pair of
+/// points in O(n log n).
+///
+/// The distance splits into an x part and a y part, so each
+/// axis can be done on its own. With the coordinates sorted,
+/// the `i`th one is at least as big as each of the `i`
+/// before it, so it adds `i * c[i] - (c[0] + ... + c[i-1])`.
+pub fn pairwise_distance_sum(points: &[(u64, u64)]) -> u128 {
+    let axis = |mut coords: Vec<u64>| {
+        coords.sort_unstable();
+        let mut prefix: u128 = 0;
+        let mut total: u128 = 0;
+        for (i, &c) in coords.iter().enumerate() {
+            total += i as u128 * c as u128 - prefix;
+            prefix += c as u128;
+        }
+        total
+    };
+    axis(points.iter().map(|p| p.0).collect()) + axis(points.iter().map(|p| p.1).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Galaxies {
+        Galaxies::parse(&[
+            "...#......",
+            ".......#..",
+            "#.........",
+            "..........",
+            "......#...",
+            ".#........",
+            ".........#",
+            "..........",
+            ".......#..",
+            "#...#.....",
+        ])
+    }
+
+    #[test]
+    fn test_expand() -> Result<()> {
+        let g = example();
+        assert_eq!(g.points().len(), 9);
+        let expanded = g.expand(2)?;
+        assert_eq!(expanded[0], (4, 0));
+        assert_eq!(expanded[8], (5, 11));
+        assert_eq!(g.expand(1)?, g.points());
+
+        // (There are up to 3 empty columns before a galaxy)...
+        assert!(g.expand(u64::MAX / 4).is_ok());
+        assert!(g.expand(u64::MAX / 2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_factor() {
+        assert_eq!(parse_factor("1").unwrap(), 1);
+        assert_eq!(parse_factor("1000000").unwrap(), 1_000_000);
+        assert!(parse_factor("0").is_err());
+        assert!(parse_factor("-1").is_err());
+    }
+
+    #[test]
+    fn test_distance_sum() -> Result<()> {
+        let g = example();
+        assert_eq!(g.distance_sum(2)?, 374);
+        assert_eq!(g.distance_sum(10)?, 1030);
+        assert_eq!(g.distance_sum(100)?, 8410);
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches_brute_force() -> Result<()> {
+        let points = example().expand(1_000_000)?;
+        let mut brute: u128 = 0;
+        for (i, a) in points.iter().enumerate() {
+            for b in &points[i + 1..] {
+                brute += (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as u128;
+            }
+        }
+        assert_eq!(pairwise_distance_sum(&points), brute);
+        assert_eq!(pairwise_distance_sum(&[]), 0);
+        Ok(())
+    }
+}
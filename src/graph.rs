@@ -0,0 +1,687 @@
+use crate::dot::Dot;
+use anyhow::{anyhow, Result};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+/// Maps string labels to dense `u32` ids in the order
+/// they're first seen, and back again.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    labels: Vec<String>,
+    ids: HashMap<String, u32>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the id for `label`, assigning the next
+    /// free id if it hasn't been seen before.
+    pub fn intern(&mut self, label: &str) -> u32 {
+        if let Some(&id) = self.ids.get(label) {
+            return id;
+        }
+        let id = self.labels.len() as u32;
+        self.labels.push(label.to_string());
+        self.ids.insert(label.to_string(), id);
+        id
+    }
+
+    /// Returns the id for `label` without interning it.
+    pub fn get(&self, label: &str) -> Option<u32> {
+        self.ids.get(label).copied()
+    }
+
+    /// Returns the label for `id`.
+    ///
+    /// Panics if `id` wasn't returned by this interner.
+    pub fn resolve(&self, id: u32) -> &str {
+        &self.labels[id as usize]
+    }
+
+    pub fn len(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+/// A directed or undirected graph whose nodes are
+/// identified by string labels.
+///
+/// Labels are interned to dense `u32` ids so algorithms
+/// can work with plain vectors instead of hashing strings.
+/// Adjacency lists keep edges in insertion order.
+#[derive(Debug, Clone, Default)]
+pub struct Graph {
+    directed: bool,
+    names: Interner,
+    out: Vec<Vec<u32>>,
+    inc: Vec<Vec<u32>>,
+    edges: Vec<(u32, u32)>,
+    edge_labels: Vec<Option<String>>,
+}
+
+impl Graph {
+    /// Creates an empty undirected graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty directed graph.
+    pub fn new_directed() -> Self {
+        Self {
+            directed: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Returns the id for `label`, adding a new node
+    /// if it hasn't been seen before.
+    pub fn add_node(&mut self, label: &str) -> u32 {
+        let id = self.names.intern(label);
+        if id as usize == self.out.len() {
+            self.out.push(Vec::new());
+            self.inc.push(Vec::new());
+        }
+        id
+    }
+
+    /// Adds an edge from `a` to `b` (or between them, for
+    /// an undirected graph), creating either node if needed.
+    pub fn add_edge(&mut self, a: &str, b: &str) -> (u32, u32) {
+        let a = self.add_node(a);
+        let b = self.add_node(b);
+        self.add_edge_ids(a, b);
+        (a, b)
+    }
+
+    /// Like `add_edge`, but attaches a label to the edge
+    /// (eg the direction taken to follow it).
+    pub fn add_labeled_edge(&mut self, a: &str, b: &str, label: &str) -> (u32, u32) {
+        let ids = self.add_edge(a, b);
+        if let Some(l) = self.edge_labels.last_mut() {
+            *l = Some(label.to_string());
+        }
+        ids
+    }
+
+    /// Adds an edge between two existing node ids.
+    pub fn add_edge_ids(&mut self, a: u32, b: u32) {
+        self.edges.push((a, b));
+        self.edge_labels.push(None);
+        self.out[a as usize].push(b);
+        if self.directed {
+            self.inc[b as usize].push(a);
+        } else if a != b {
+            self.out[b as usize].push(a);
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.out.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn id(&self, label: &str) -> Option<u32> {
+        self.names.get(label)
+    }
+
+    pub fn label(&self, id: u32) -> &str {
+        self.names.resolve(id)
+    }
+
+    /// Returns the nodes reachable from `id` in one step,
+    /// in the order the edges were added.
+    pub fn neighbors(&self, id: u32) -> &[u32] {
+        &self.out[id as usize]
+    }
+
+    /// Returns the nodes with an edge into `id`. For an
+    /// undirected graph this is the same as `neighbors`.
+    pub fn predecessors(&self, id: u32) -> &[u32] {
+        if self.directed {
+            &self.inc[id as usize]
+        } else {
+            &self.out[id as usize]
+        }
+    }
+
+    /// Returns every edge once, in the order they were added.
+    pub fn edges(&self) -> &[(u32, u32)] {
+        &self.edges
+    }
+
+    /// Returns the label of the `i`th edge, if it has one.
+    pub fn edge_label(&self, i: usize) -> Option<&str> {
+        self.edge_labels.get(i)?.as_deref()
+    }
+
+    /// Iterates over all node ids.
+    pub fn nodes(&self) -> impl Iterator<Item = u32> {
+        0..self.node_count() as u32
+    }
+
+    /// Splits the graph into connected components (weakly
+    /// connected, for a directed graph), each sorted by id.
+    pub fn components(&self) -> Vec<Vec<u32>> {
+        let mut seen = vec![false; self.node_count()];
+        let mut components = Vec::new();
+        for start in self.nodes() {
+            if seen[start as usize] {
+                continue;
+            }
+            seen[start as usize] = true;
+            let mut component = vec![start];
+            let mut queue = VecDeque::from([start]);
+            while let Some(node) = queue.pop_front() {
+                let next = self.neighbors(node).iter().chain(self.predecessors(node));
+                for &m in next {
+                    if !seen[m as usize] {
+                        seen[m as usize] = true;
+                        component.push(m);
+                        queue.push_back(m);
+                    }
+                }
+            }
+            component.sort_unstable();
+            components.push(component);
+        }
+        components
+    }
+
+    /// Returns the nodes of a directed graph in an order
+    /// where every edge points forwards (Kahn's algorithm).
+    pub fn topo_sort(&self) -> Result<Vec<u32>> {
+        if !self.directed {
+            return Err(anyhow!("Can't topologically sort an undirected graph"));
+        }
+        let mut in_degree = self.inc.iter().map(|p| p.len()).collect::<Vec<_>>();
+        let mut queue = self
+            .nodes()
+            .filter(|&i| in_degree[i as usize] == 0)
+            .collect::<VecDeque<_>>();
+        let mut order = Vec::with_capacity(self.node_count());
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for &m in self.neighbors(node) {
+                in_degree[m as usize] -= 1;
+                if in_degree[m as usize] == 0 {
+                    queue.push_back(m);
+                }
+            }
+        }
+        if order.len() != self.node_count() {
+            let cycle = self.find_cycle().unwrap_or_default();
+            let cycle = cycle.iter().map(|&i| self.label(i)).collect::<Vec<_>>();
+            return Err(anyhow!("Graph has a cycle: {}", cycle.join(" -> ")));
+        }
+        Ok(order)
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// Finds a cycle in the graph, returned as the list of
+    /// nodes along it (without repeating the first node).
+    ///
+    /// In an undirected graph, going straight back along
+    /// the edge just taken doesn't count, but a second edge
+    /// between the same two nodes does.
+    pub fn find_cycle(&self) -> Option<Vec<u32>> {
+        // 0 = unvisited, 1 = on the stack, 2 = done...
+        let mut state = vec![0u8; self.node_count()];
+        let mut parent = vec![u32::MAX; self.node_count()];
+        for start in self.nodes() {
+            if state[start as usize] != 0 {
+                continue;
+            }
+
+            // Iterative DFS, keeping (node, next-neighbor-index)
+            // on the stack...
+            let mut stack = vec![(start, 0usize)];
+            state[start as usize] = 1;
+            while let Some(&mut (node, ref mut i)) = stack.last_mut() {
+                let ns = self.neighbors(node);
+                if *i == ns.len() {
+                    state[node as usize] = 2;
+                    stack.pop();
+                    continue;
+                }
+                let m = ns[*i];
+                *i += 1;
+
+                // Skip the edge we came in on (once)...
+                if !self.directed && m == parent[node as usize] {
+                    let back = ns[..*i - 1].iter().filter(|&&x| x == m).count();
+                    if back == 0 {
+                        continue;
+                    }
+                }
+
+                match state[m as usize] {
+                    0 => {
+                        state[m as usize] = 1;
+                        parent[m as usize] = node;
+                        stack.push((m, 0));
+                    }
+                    1 => {
+                        let at = stack.iter().position(|&(n, _)| n == m)?;
+                        return Some(stack[at..].iter().map(|&(n, _)| n).collect());
+                    }
+                    _ => {}
+                }
+            }
+        }
+        None
+    }
+
+    /// Splits the graph into strongly connected components
+    /// (groups of nodes that can all reach each other), using
+    /// Tarjan's algorithm.
+    ///
+    /// Components come out in reverse topological order: any
+    /// edge leaving a component goes to one listed before it.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<u32>> {
+        const UNSEEN: u32 = u32::MAX;
+        let n = self.node_count();
+        let mut index = vec![UNSEEN; n];
+        let mut low = vec![0u32; n];
+        let mut on_stack = vec![false; n];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+        for start in self.nodes() {
+            if index[start as usize] != UNSEEN {
+                continue;
+            }
+
+            // Iterative DFS, keeping (node, next-neighbor-index)
+            // on the stack...
+            let mut dfs = vec![(start, 0usize)];
+            index[start as usize] = next_index;
+            low[start as usize] = next_index;
+            next_index += 1;
+            stack.push(start);
+            on_stack[start as usize] = true;
+            while let Some(&mut (node, ref mut i)) = dfs.last_mut() {
+                let ns = self.neighbors(node);
+                if *i < ns.len() {
+                    let m = ns[*i];
+                    *i += 1;
+                    if index[m as usize] == UNSEEN {
+                        index[m as usize] = next_index;
+                        low[m as usize] = next_index;
+                        next_index += 1;
+                        stack.push(m);
+                        on_stack[m as usize] = true;
+                        dfs.push((m, 0));
+                    } else if on_stack[m as usize] {
+                        low[node as usize] = low[node as usize].min(index[m as usize]);
+                    }
+                    continue;
+                }
+
+                // Done with this node, so pass its low-link up
+                // and pop its component if it's the root...
+                dfs.pop();
+                if let Some(&(parent, _)) = dfs.last() {
+                    low[parent as usize] = low[parent as usize].min(low[node as usize]);
+                }
+                if low[node as usize] == index[node as usize] {
+                    let mut component = Vec::new();
+                    while let Some(m) = stack.pop() {
+                        on_stack[m as usize] = false;
+                        component.push(m);
+                        if m == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    /// Builds a DOT description of the graph, using node
+    /// ids as DOT ids and labels as DOT labels, so paths of
+    /// ids can be highlighted with `Dot::highlight_path`.
+    pub fn dot(&self) -> Dot {
+        let mut dot = Dot::new(self.directed);
+        for id in self.nodes() {
+            dot.node(&id.to_string(), self.label(id));
+        }
+        for (i, &(a, b)) in self.edges.iter().enumerate() {
+            dot.edge(&a.to_string(), &b.to_string(), self.edge_label(i));
+        }
+        dot
+    }
+
+    /// Renders the graph in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        self.dot().render()
+    }
+}
+
+/// The result of a minimum-cut search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    /// Number of edges crossing the cut.
+    pub weight: usize,
+
+    /// The edges crossing the cut, as `(a, b)` with `a`
+    /// on the first side.
+    pub edges: Vec<(u32, u32)>,
+
+    /// The node ids on the first side of the cut.
+    pub side: Vec<u32>,
+
+    /// The sizes of the two sides of the cut.
+    pub sizes: (usize, usize),
+}
+
+impl MinCut {
+    /// Returns the product of the two component sizes.
+    pub fn product(&self) -> usize {
+        self.sizes.0 * self.sizes.1
+    }
+}
+
+/// Finds a global minimum cut of the undirected graph `g`
+/// using the Stoer–Wagner algorithm.
+///
+/// Each phase grows a set of nodes by repeatedly adding
+/// the "most tightly connected" one (tracked with a lazy
+/// max-heap) and then merges the last two nodes added.
+/// The lightest cut-of-the-phase seen is the minimum cut.
+pub fn min_cut(g: &Graph) -> Result<MinCut> {
+    if g.is_directed() {
+        return Err(anyhow!("Can't find a min cut of a directed graph"));
+    }
+    let n = g.node_count();
+    if n < 2 {
+        return Err(anyhow!("Need at least 2 nodes to cut, found {}", n));
+    }
+
+    // Build the weighted adjacency for the (shrinking)
+    // set of merged super-nodes...
+    let mut weights: Vec<HashMap<usize, usize>> = vec![HashMap::new(); n];
+    for &(a, b) in g.edges().iter().filter(|(a, b)| a != b) {
+        let (a, b) = (a as usize, b as usize);
+        *weights[a].entry(b).or_insert(0) += 1;
+        *weights[b].entry(a).or_insert(0) += 1;
+    }
+    let mut members: Vec<Vec<u32>> = g.nodes().map(|i| vec![i]).collect();
+    let mut active: Vec<bool> = vec![true; n];
+
+    let mut best_weight = usize::MAX;
+    let mut best_side: Vec<u32> = Vec::new();
+
+    for phase in 0..n - 1 {
+        let start = (0..n)
+            .find(|&i| active[i])
+            .ok_or_else(|| anyhow!("No active nodes in phase {}", phase))?;
+
+        // Grow the set one node at a time...
+        let mut in_set = vec![false; n];
+        let mut keys = vec![0usize; n];
+        let mut heap = BinaryHeap::new();
+        heap.push((0usize, start));
+        let mut prev = start;
+        let mut last = start;
+        let mut added = 0;
+        let remaining = n - phase;
+        while let Some((key, node)) = heap.pop() {
+            if in_set[node] || key != keys[node] {
+                continue;
+            }
+            in_set[node] = true;
+            prev = last;
+            last = node;
+            added += 1;
+            if added == remaining {
+                break;
+            }
+            for (&m, &w) in &weights[node] {
+                if !in_set[m] {
+                    keys[m] += w;
+                    heap.push((keys[m], m));
+                }
+            }
+        }
+
+        // A disconnected graph has a cut of zero...
+        if added < remaining {
+            let side = (0..n)
+                .filter(|&i| in_set[i])
+                .flat_map(|i| members[i].clone())
+                .collect();
+            return Ok(build_cut(g, side));
+        }
+
+        // Check the cut-of-the-phase...
+        if keys[last] < best_weight {
+            best_weight = keys[last];
+            best_side = members[last].clone();
+        }
+
+        // Merge `last` into `prev`...
+        let moved = std::mem::take(&mut weights[last]);
+        for (m, w) in moved {
+            weights[m].remove(&last);
+            if m == prev {
+                continue;
+            }
+            *weights[prev].entry(m).or_insert(0) += w;
+            *weights[m].entry(prev).or_insert(0) += w;
+        }
+        let moved = std::mem::take(&mut members[last]);
+        members[prev].extend(moved);
+        active[last] = false;
+    }
+
+    Ok(build_cut(g, best_side))
+}
+
+/// Builds the full `MinCut` description for the
+/// partition with `side` on one side.
+fn build_cut(g: &Graph, mut side: Vec<u32>) -> MinCut {
+    side.sort_unstable();
+    let mut on_side = vec![false; g.node_count()];
+    for &i in &side {
+        on_side[i as usize] = true;
+    }
+    let edges = g
+        .edges()
+        .iter()
+        .filter(|&&(a, b)| on_side[a as usize] != on_side[b as usize])
+        .map(|&(a, b)| if on_side[a as usize] { (a, b) } else { (b, a) })
+        .collect::<Vec<_>>();
+    MinCut {
+        weight: edges.len(),
+        sizes: (side.len(), g.node_count() - side.len()),
+        edges,
+        side,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn directed(edges: &[(&str, &str)]) -> Graph {
+        let mut g = Graph::new_directed();
+        for (a, b) in edges {
+            g.add_edge(a, b);
+        }
+        g
+    }
+
+    #[test]
+    fn test_interner() {
+        let mut names = Interner::new();
+        assert_eq!(names.intern("AAA"), 0);
+        assert_eq!(names.intern("BBB"), 1);
+        assert_eq!(names.intern("AAA"), 0);
+        assert_eq!(names.get("BBB"), Some(1));
+        assert_eq!(names.get("CCC"), None);
+        assert_eq!(names.resolve(1), "BBB");
+        assert_eq!(names.len(), 2);
+    }
+
+    #[test]
+    fn test_add_edge() {
+        let mut g = Graph::new();
+        g.add_edge("a", "b");
+        g.add_edge("b", "c");
+        assert_eq!(g.node_count(), 3);
+        assert_eq!(g.edge_count(), 2);
+        assert_eq!(g.id("b"), Some(1));
+        assert_eq!(g.label(2), "c");
+        assert_eq!(g.neighbors(1), &[0, 2]);
+
+        let g = directed(&[("a", "b"), ("b", "c"), ("a", "c")]);
+        assert_eq!(g.neighbors(0), &[1, 2]);
+        assert_eq!(g.neighbors(2), &[] as &[u32]);
+        assert_eq!(g.predecessors(2), &[1, 0]);
+    }
+
+    #[test]
+    fn test_components() {
+        let mut g = Graph::new();
+        g.add_edge("a", "b");
+        g.add_edge("c", "d");
+        g.add_edge("d", "e");
+        g.add_node("f");
+        assert_eq!(g.components(), vec![vec![0, 1], vec![2, 3, 4], vec![5]]);
+
+        // Directed edges still join weak components...
+        let g = directed(&[("a", "b"), ("c", "b")]);
+        assert_eq!(g.components(), vec![vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn test_topo_sort() -> Result<()> {
+        let g = directed(&[("shirt", "tie"), ("tie", "jacket"), ("pants", "shoes"), ("pants", "jacket")]);
+        let order = g.topo_sort()?;
+        let pos = |l: &str| order.iter().position(|&i| g.label(i) == l).unwrap();
+        for &(a, b) in g.edges() {
+            assert!(pos(g.label(a)) < pos(g.label(b)));
+        }
+        assert!(Graph::new().topo_sort().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let g = directed(&[("a", "b"), ("b", "c"), ("a", "c")]);
+        assert!(!g.has_cycle());
+
+        let g = directed(&[("a", "b"), ("b", "c"), ("c", "a")]);
+        assert_eq!(g.find_cycle(), Some(vec![0, 1, 2]));
+        assert!(g.topo_sort().is_err());
+
+        let g = directed(&[("a", "a")]);
+        assert_eq!(g.find_cycle(), Some(vec![0]));
+
+        // An undirected tree has no cycles, but a doubled
+        // edge does...
+        let mut g = Graph::new();
+        g.add_edge("a", "b");
+        g.add_edge("b", "c");
+        assert!(!g.has_cycle());
+        g.add_edge("b", "a");
+        assert_eq!(g.find_cycle(), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        // a <-> b -> c <-> d, e on its own...
+        let mut g = directed(&[
+            ("a", "b"),
+            ("b", "a"),
+            ("b", "c"),
+            ("c", "d"),
+            ("d", "c"),
+        ]);
+        g.add_node("e");
+        let mut sccs = g.strongly_connected_components();
+        for c in &mut sccs {
+            c.sort();
+        }
+        assert_eq!(sccs, vec![vec![2, 3], vec![0, 1], vec![4]]);
+
+        let g = directed(&[("a", "b"), ("b", "c")]);
+        assert_eq!(g.strongly_connected_components(), vec![vec![2], vec![1], vec![0]]);
+    }
+
+    #[test]
+    fn test_to_dot() {
+        let mut g = directed(&[("AAA", "BBB")]);
+        g.add_labeled_edge("BBB", "AAA", "L");
+        assert_eq!(g.edge_label(0), None);
+        assert_eq!(g.edge_label(1), Some("L"));
+        assert_eq!(
+            g.to_dot(),
+            [
+                "digraph {",
+                "    \"0\" [label=\"AAA\"];",
+                "    \"1\" [label=\"BBB\"];",
+                "    \"0\" -> \"1\";",
+                "    \"1\" -> \"0\" [label=\"L\"];",
+                "}",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_min_cut_two_triangles() -> Result<()> {
+        // Two triangles joined by a single bridge...
+        let mut g = Graph::new();
+        for (a, b) in [("a", "b"), ("b", "c"), ("c", "a"), ("x", "y"), ("y", "z"), ("z", "x")] {
+            g.add_edge(a, b);
+        }
+        g.add_edge("c", "x");
+        let cut = min_cut(&g)?;
+        assert_eq!(cut.weight, 1);
+        assert_eq!(cut.product(), 9);
+        let (a, b) = cut.edges[0];
+        let mut labels = vec![g.label(a), g.label(b)];
+        labels.sort();
+        assert_eq!(labels, vec!["c", "x"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_cut_disconnected() -> Result<()> {
+        let mut g = Graph::new();
+        g.add_edge("a", "b");
+        g.add_edge("c", "d");
+        g.add_edge("d", "e");
+        let cut = min_cut(&g)?;
+        assert_eq!(cut.weight, 0);
+        assert_eq!(cut.product(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_cut_too_small() {
+        let mut g = Graph::new();
+        g.add_node("a");
+        assert!(min_cut(&g).is_err());
+        assert!(min_cut(&Graph::new_directed()).is_err());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::has_flag;
+
+/// The command line flag that asks a solution to draw
+/// its grid in the terminal.
+pub const VISUALIZE_FLAG: &str = "--visualize";
+
+/// Colors that can be used for grid overlays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+
+impl Color {
+    /// The ANSI escape code that switches to this color.
+    fn ansi(&self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+            Color::Gray => "\x1b[90m",
+        }
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    ch: char,
+    color: Option<Color>,
+}
+
+/// A grid of characters for drawing puzzle state in
+/// the terminal.
+///
+/// Start from the puzzle's own characters and then
+/// overlay sets of points (eg a path or the energized
+/// tiles) with a replacement character and/or a color.
+/// Later overlays are drawn on top of earlier ones.
+///
+/// Points are `(row, col)` pairs. Points outside the
+/// grid are ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    cells: Vec<Vec<Cell>>,
+}
+
+impl Grid {
+    /// Creates a grid from lines of text.
+    pub fn from_lines<S: AsRef<str>>(lines: &[S]) -> Self {
+        let cells = lines
+            .iter()
+            .map(|l| l.as_ref().chars().map(|ch| Cell { ch, color: None }).collect())
+            .collect();
+        Self { cells }
+    }
+
+    /// Creates a grid by mapping each of a puzzle's cells
+    /// to a character.
+    pub fn from_cells<T>(cells: &[Vec<T>], f: impl Fn(&T) -> char) -> Self {
+        let cells = cells
+            .iter()
+            .map(|row| row.iter().map(|c| Cell { ch: f(c), color: None }).collect())
+            .collect();
+        Self { cells }
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.cells.iter().map(|r| r.len()).max().unwrap_or(0)
+    }
+
+    /// Returns the character at `(row, col)`.
+    pub fn get(&self, row: usize, col: usize) -> Option<char> {
+        self.cells.get(row)?.get(col).map(|c| c.ch)
+    }
+
+    /// Replaces every character in the grid with `f(ch)`.
+    pub fn map_chars(&mut self, f: impl Fn(char) -> char) -> &mut Self {
+        for cell in self.cells.iter_mut().flatten() {
+            cell.ch = f(cell.ch);
+        }
+        self
+    }
+
+    /// Draws every point in `points` with `ch` (or keeps
+    /// the current character if `None`) in `color`.
+    pub fn overlay<I>(&mut self, points: I, ch: Option<char>, color: Option<Color>) -> &mut Self
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        for (row, col) in points {
+            if let Some(cell) = self.cells.get_mut(row).and_then(|r| r.get_mut(col)) {
+                if let Some(ch) = ch {
+                    cell.ch = ch;
+                }
+                if color.is_some() {
+                    cell.color = color;
+                }
+            }
+        }
+        self
+    }
+
+    /// Like `overlay`, but with a character per point (eg
+    /// an arrow showing which way a path goes).
+    pub fn overlay_chars<I>(&mut self, points: I, color: Option<Color>) -> &mut Self
+    where
+        I: IntoIterator<Item = ((usize, usize), char)>,
+    {
+        for (point, ch) in points {
+            self.overlay([point], Some(ch), color);
+        }
+        self
+    }
+
+    /// Renders the grid as text, one line per row. If
+    /// `ansi` is set, colored cells are wrapped in ANSI
+    /// escape codes.
+    pub fn render(&self, ansi: bool) -> String {
+        let mut out = String::new();
+        for row in &self.cells {
+            let mut current: Option<Color> = None;
+            for cell in row {
+                let color = if ansi { cell.color } else { None };
+                if color != current {
+                    if current.is_some() {
+                        out.push_str(ANSI_RESET);
+                    }
+                    if let Some(c) = color {
+                        out.push_str(c.ansi());
+                    }
+                    current = color;
+                }
+                out.push(cell.ch);
+            }
+            if current.is_some() {
+                out.push_str(ANSI_RESET);
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Prints the grid if the program was run with the
+    /// `--visualize` flag. Colors are skipped if the
+    /// `NO_COLOR` environment variable is set.
+    pub fn print_if_requested(&self) {
+        if has_flag(VISUALIZE_FLAG) {
+            let ansi = std::env::var_os("NO_COLOR").is_none();
+            print!("{}", self.render(ansi));
+        }
+    }
+}
+
+/// Maps the puzzle's pipe characters (`|-LJ7F`) to
+/// box-drawing characters. Anything else is unchanged.
+pub fn box_drawing(ch: char) -> char {
+    match ch {
+        '|' => '│',
+        '-' => '─',
+        'L' => '└',
+        'J' => '┘',
+        '7' => '┐',
+        'F' => '┌',
+        _ => ch,
+    }
+}
+
+/// Returns an arrow pointing from `from` to `to`, for
+/// two orthogonally adjacent `(row, col)` points.
+pub fn arrow(from: (usize, usize), to: (usize, usize)) -> char {
+    use std::cmp::Ordering::*;
+    match (to.0.cmp(&from.0), to.1.cmp(&from.1)) {
+        (Less, _) => '^',
+        (Greater, _) => 'v',
+        (_, Less) => '<',
+        (_, Greater) => '>',
+        (Equal, Equal) => '*',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_plain() {
+        let mut g = Grid::from_lines(&["F-7", "|.|", "L-J"]);
+        g.map_chars(box_drawing)
+            .overlay([(1, 1)], Some('I'), None)
+            .overlay([(5, 5)], Some('X'), None);
+        assert_eq!(g.render(false), "┌─┐\n│I│\n└─┘\n");
+        assert_eq!(g.height(), 3);
+        assert_eq!(g.width(), 3);
+        assert_eq!(g.get(1, 1), Some('I'));
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let mut g = Grid::from_cells(&[vec![1, 2, 3]], |n| char::from(b'0' + *n as u8));
+        g.overlay([(0, 0), (0, 1)], Some('#'), Some(Color::Red));
+        assert_eq!(g.render(true), "\x1b[31m##\x1b[0m3\n");
+        assert_eq!(g.render(false), "##3\n");
+    }
+
+    #[test]
+    fn test_arrows() {
+        let path = [(0, 0), (0, 1), (1, 1), (1, 0), (0, 0)];
+        let arrows = path
+            .windows(2)
+            .map(|w| arrow(w[0], w[1]))
+            .collect::<String>();
+        assert_eq!(arrows, ">v<^");
+
+        let mut g = Grid::from_lines(&["..", ".."]);
+        g.overlay_chars(path.windows(2).map(|w| (w[0], arrow(w[0], w[1]))), None);
+        assert_eq!(g.render(false), ">v\n^<\n");
+    }
+}
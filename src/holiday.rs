@@ -0,0 +1,211 @@
+use std::hash::{BuildHasher, Hasher};
+
+/// The number of buckets (boxes) in a [`BoxMap`], which is
+/// every value the HASH algorithm can produce.
+pub const BUCKETS: usize = 256;
+
+/// The Holiday ASCII String Helper algorithm (day 15) as a
+/// [`Hasher`]: for each byte, add it, multiply by 17 and
+/// keep the remainder mod 256.
+///
+/// Note that `str`'s `Hash` impl writes an extra `0xff`
+/// byte after the string, so to get the puzzle's hash of a
+/// string, `write` its bytes directly (or use [`hash`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HolidayHasher {
+    state: u8,
+}
+
+impl Hasher for HolidayHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.state = self.state.wrapping_add(b).wrapping_mul(17);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state as u64
+    }
+}
+
+/// Builds [`HolidayHasher`]s.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HolidayHash;
+
+impl BuildHasher for HolidayHash {
+    type Hasher = HolidayHasher;
+
+    fn build_hasher(&self) -> HolidayHasher {
+        HolidayHasher::default()
+    }
+}
+
+/// Returns the HASH of `s`.
+pub fn hash(s: &str) -> u8 {
+    let mut h = HolidayHasher::default();
+    h.write(s.as_bytes());
+    h.finish() as u8
+}
+
+/// A hash map with string keys and a fixed set of 256
+/// buckets, where each bucket keeps its entries in the order
+/// they were first inserted (like the lens boxes on day 15).
+#[derive(Debug, Clone)]
+pub struct BoxMap<V, S = HolidayHash> {
+    buckets: Vec<Vec<(String, V)>>,
+    hasher: S,
+    len: usize,
+}
+
+impl<V> BoxMap<V> {
+    pub fn new() -> Self {
+        Self::with_hasher(HolidayHash)
+    }
+}
+
+impl<V> Default for BoxMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, S: BuildHasher> BoxMap<V, S> {
+    pub fn with_hasher(hasher: S) -> Self {
+        Self {
+            buckets: (0..BUCKETS).map(|_| Vec::new()).collect(),
+            hasher,
+            len: 0,
+        }
+    }
+
+    /// Returns the bucket that `key` belongs in.
+    pub fn bucket_of(&self, key: &str) -> usize {
+        let mut h = self.hasher.build_hasher();
+        h.write(key.as_bytes());
+        (h.finish() % BUCKETS as u64) as usize
+    }
+
+    fn position(&self, key: &str) -> (usize, Option<usize>) {
+        let b = self.bucket_of(key);
+        (b, self.buckets[b].iter().position(|(k, _)| k == key))
+    }
+
+    /// Sets the value for `key`, returning the old value. A
+    /// key that's already there keeps its place in its bucket;
+    /// a new key goes at the back.
+    pub fn insert(&mut self, key: &str, value: V) -> Option<V> {
+        match self.position(key) {
+            (b, Some(i)) => Some(std::mem::replace(&mut self.buckets[b][i].1, value)),
+            (b, None) => {
+                self.buckets[b].push((key.to_string(), value));
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    /// Removes `key`, moving everything behind it in its
+    /// bucket forward.
+    pub fn remove(&mut self, key: &str) -> Option<V> {
+        let (b, i) = self.position(key);
+        let (_, value) = self.buckets[b].remove(i?);
+        self.len -= 1;
+        Some(value)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&V> {
+        let (b, i) = self.position(key);
+        i.map(|i| &self.buckets[b][i].1)
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The entries in bucket `b`, in order.
+    pub fn bucket(&self, b: usize) -> &[(String, V)] {
+        &self.buckets[b]
+    }
+
+    /// Iterates over the non-empty buckets, with their
+    /// indexes, in order.
+    pub fn buckets(&self) -> impl Iterator<Item = (usize, &[(String, V)])> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| !b.is_empty())
+            .map(|(i, b)| (i, b.as_slice()))
+    }
+
+    /// Iterates over every entry, bucket by bucket and in
+    /// order within each bucket.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &V)> {
+        self.buckets.iter().flatten().map(|(k, v)| (k.as_str(), v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_hash() {
+        assert_eq!(hash("HASH"), 52);
+        assert_eq!(hash("rn=1"), 30);
+        assert_eq!(hash("cm-"), 253);
+        assert_eq!(hash("qp=3"), 97);
+        assert_eq!(hash(""), 0);
+
+        // One byte at a time is the same as all at once...
+        let mut h = HolidayHash.build_hasher();
+        h.write_u8(b'H');
+        assert_eq!(h.finish(), 200);
+        h.write(b"ASH");
+        assert_eq!(h.finish(), 52);
+    }
+
+    #[test]
+    fn test_std_hash_map() {
+        // It's a real (if terrible) hasher...
+        let mut map = HashMap::with_hasher(HolidayHash);
+        map.insert("rn", 1);
+        map.insert("cm", 2);
+        assert_eq!(map.get("rn"), Some(&1));
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_box_map() {
+        let mut map = BoxMap::new();
+        assert_eq!(map.insert("rn", 1), None);
+        assert_eq!(map.insert("cm", 2), None);
+        assert_eq!(map.insert("qp", 3), None);
+        assert_eq!(map.bucket_of("rn"), 0);
+        assert_eq!(map.bucket_of("qp"), 1);
+        assert_eq!(map.len(), 3);
+
+        // Replacing keeps the order...
+        assert_eq!(map.insert("rn", 9), Some(1));
+        let keys = map.iter().map(|(k, _)| k).collect::<Vec<_>>();
+        assert_eq!(keys, vec!["rn", "cm", "qp"]);
+
+        assert_eq!(map.remove("rn"), Some(9));
+        assert_eq!(map.remove("rn"), None);
+        assert!(!map.contains_key("rn"));
+        assert_eq!(map.bucket(0), &[("cm".to_string(), 2)]);
+        assert_eq!(
+            map.buckets().map(|(i, _)| i).collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        assert_eq!(map.len(), 2);
+    }
+}
@@ -0,0 +1,403 @@
+use crate::error::{AocError, ParseError};
+use anyhow::Result;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::str::FromStr;
+
+/// How to clean up raw input before a day's parser sees it.
+///
+/// Line endings are always normalized to `\n` and a UTF-8
+/// byte order mark is always dropped. Tabs and non-ASCII
+/// characters are left alone, but (unless the day says it
+/// expects them) produce a [`Warning`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Normalize {
+    trim_end: bool,
+    allow_tabs: bool,
+    allow_non_ascii: bool,
+}
+
+/// Something suspicious in the input (see [`Normalize`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warning {
+    /// What was found (eg "tab").
+    pub what: &'static str,
+
+    /// How many there were, and where the first one was.
+    pub count: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "input has {} {}{} (first at line {}, column {})",
+            self.count,
+            self.what,
+            if self.count == 1 { "" } else { "s" },
+            self.line,
+            self.column
+        )
+    }
+}
+
+impl Normalize {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also drops trailing whitespace, including blank lines
+    /// and the final line ending (for inputs that are read
+    /// as one long string rather than as lines).
+    pub fn trim_end(mut self) -> Self {
+        self.trim_end = true;
+        self
+    }
+
+    /// Doesn't warn about tabs.
+    pub fn allow_tabs(mut self) -> Self {
+        self.allow_tabs = true;
+        self
+    }
+
+    /// Doesn't warn about non-ASCII characters.
+    pub fn allow_non_ascii(mut self) -> Self {
+        self.allow_non_ascii = true;
+        self
+    }
+
+    /// Returns the cleaned up text, along with any warnings.
+    pub fn apply(&self, raw: &str) -> (String, Vec<Warning>) {
+        let raw = raw.strip_prefix('\u{feff}').unwrap_or(raw);
+        let mut text = raw.replace("\r\n", "\n");
+        if self.trim_end {
+            text.truncate(text.trim_end().len());
+        }
+
+        // Look for anything the day doesn't expect...
+        let mut tabs: Option<Warning> = None;
+        let mut non_ascii: Option<Warning> = None;
+        for (i, line) in text.lines().enumerate() {
+            for (j, c) in line.chars().enumerate() {
+                let found = match c {
+                    '\t' if !self.allow_tabs => (&mut tabs, "tab"),
+                    c if !c.is_ascii() && !self.allow_non_ascii => {
+                        (&mut non_ascii, "non-ASCII character")
+                    }
+                    _ => continue,
+                };
+                let w = found.0.get_or_insert(Warning {
+                    what: found.1,
+                    count: 0,
+                    line: i + 1,
+                    column: j + 1,
+                });
+                w.count += 1;
+            }
+        }
+        (text, tabs.into_iter().chain(non_ascii).collect())
+    }
+}
+
+/// A whole input, loaded once and handed out as lines
+/// borrowed from the one buffer (rather than a `String`
+/// per line, like [`crate::load_input_lines`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    pub fn new(text: String) -> Self {
+        Self { text }
+    }
+
+    /// Loads the input data for the `d`th day.
+    pub fn load(d: u8) -> Result<Self, AocError> {
+        Ok(Self::new(crate::load_input(d)?))
+    }
+
+    /// Loads the input data for the binary `name` (eg
+    /// `file!()`).
+    pub fn load_by_name(name: &str) -> Result<Self, AocError> {
+        Ok(Self::new(crate::load_input_by_name(name)?))
+    }
+
+    /// Reads everything from `r`.
+    pub fn read(mut r: impl Read) -> io::Result<Self> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        Ok(Self::new(text))
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Iterates over the lines (without their line endings).
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.text.lines()
+    }
+
+    /// The lines, split into paragraphs (see [`paragraphs`]).
+    pub fn paragraphs(&self) -> Vec<Vec<&str>> {
+        paragraphs(&self.text)
+    }
+
+    /// Parses each line with `f` (see [`crate::parse_lines`]).
+    pub fn parse_lines<T, F>(&self, f: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&str) -> Result<T>,
+    {
+        crate::parse_lines(&self.lines().collect::<Vec<_>>(), f)
+    }
+}
+
+/// Reads lines one at a time from a [`BufRead`], reusing a
+/// single buffer, so inputs too big to hold in memory (or
+/// to copy line by line) can still be worked through. Line
+/// endings and a byte order mark are dropped, like
+/// [`Normalize`] does.
+///
+/// Each line borrows the reader, so this can't be an
+/// `Iterator`; loop with [`LineReader::next_line`] or use
+/// [`LineReader::for_each_line`] instead.
+#[derive(Debug)]
+pub struct LineReader<R> {
+    reader: R,
+    buf: String,
+    line: usize,
+}
+
+/// Opens the input data for the `d`th day for streaming.
+pub fn open(d: u8) -> Result<LineReader<BufReader<File>>, AocError> {
+    let path = crate::input_path(d);
+    let file = File::open(&path).map_err(|e| AocError::read(d, path, e))?;
+    Ok(LineReader::new(BufReader::new(file)))
+}
+
+/// Opens the input data for the binary `name` (eg
+/// `file!()`) for streaming.
+pub fn open_by_name(name: &str) -> Result<LineReader<BufReader<File>>, AocError> {
+    open(crate::parse_filename(name)?)
+}
+
+/// Strips a trailing `\n` or `\r\n` from `line`.
+fn trim_line_ending(line: &str) -> &str {
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    line.strip_suffix('\r').unwrap_or(line)
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+            line: 0,
+        }
+    }
+
+    /// The (1-based) number of the last line read.
+    pub fn line_number(&self) -> usize {
+        self.line
+    }
+
+    /// Reads the next line (without its line ending), or
+    /// returns `None` at the end of the input.
+    pub fn next_line(&mut self) -> io::Result<Option<&str>> {
+        self.buf.clear();
+        if self.reader.read_line(&mut self.buf)? == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        let line = trim_line_ending(&self.buf);
+        if self.line == 1 {
+            return Ok(Some(line.strip_prefix('\u{feff}').unwrap_or(line)));
+        }
+        Ok(Some(line))
+    }
+
+    /// Calls `f` on each of the remaining lines, stopping at
+    /// the first error (which says which line it was on).
+    pub fn for_each_line<F>(mut self, mut f: F) -> Result<()>
+    where
+        F: FnMut(&str) -> Result<()>,
+    {
+        while let Some(line) = self.next_line()? {
+            if let Err(e) = f(line) {
+                let n = self.line;
+                return Err(crate::line_error(e, n, trim_line_ending(&self.buf)).into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits `text` into paragraphs: runs of lines separated by
+/// one or more blank lines. The lines are borrowed from
+/// `text`, and there are no empty paragraphs.
+pub fn paragraphs(text: &str) -> Vec<Vec<&str>> {
+    let mut out = vec![Vec::new()];
+    for line in text.lines() {
+        match (line.trim().is_empty(), out.last_mut()) {
+            (true, Some(last)) if !last.is_empty() => out.push(Vec::new()),
+            (false, Some(last)) => last.push(line),
+            _ => {}
+        }
+    }
+    out.retain(|p| !p.is_empty());
+    out
+}
+
+/// Iterates over the integers in `line` (as slices of it):
+/// runs of digits, including a `-` in front unless it comes
+/// right after a letter or digit (so `x=-3` has `-3` but
+/// `1-5` is `1` and `5`).
+pub fn int_strs(line: &str) -> impl Iterator<Item = &str> {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        let start = i + bytes[i..].iter().position(u8::is_ascii_digit)?;
+        let len = bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .unwrap_or(bytes.len() - start);
+        i = start + len;
+        let signed = start > 0
+            && bytes[start - 1] == b'-'
+            && (start < 2 || !bytes[start - 2].is_ascii_alphanumeric());
+        Some(&line[start - usize::from(signed)..i])
+    })
+}
+
+/// Parses every integer in `line` (see [`int_strs`]),
+/// ignoring whatever is around them.
+pub fn ints<T: FromStr>(line: &str) -> Result<Vec<T>, ParseError> {
+    int_strs(line)
+        .map(|s| {
+            s.parse::<T>()
+                .map_err(|_| ParseError::within(line, s, "number out of range"))
+        })
+        .collect()
+}
+
+/// Splits `line` into columns `width` characters wide (the
+/// last one may be narrower).
+pub fn columns(line: &str, width: usize) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        let end = rest
+            .char_indices()
+            .nth(width.max(1))
+            .map_or(rest.len(), |(i, _)| i);
+        out.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_normalize() {
+        let raw = "\u{feff}rn=1,cm-\r\nqp=3\r\n\r\n  \n";
+        let (text, warnings) = Normalize::new().apply(raw);
+        assert_eq!(text, "rn=1,cm-\nqp=3\n\n  \n");
+        assert!(warnings.is_empty());
+        let (text, _) = Normalize::new().trim_end().apply(raw);
+        assert_eq!(text, "rn=1,cm-\nqp=3");
+
+        // Unexpected characters...
+        let (_, warnings) = Normalize::new().apply("a\tb\n\tc é\n");
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            (warnings[0].count, warnings[0].line, warnings[0].column),
+            (2, 1, 2)
+        );
+        assert_eq!(
+            warnings[1].to_string(),
+            "input has 1 non-ASCII character (first at line 2, column 4)"
+        );
+        let (_, warnings) = Normalize::new()
+            .allow_tabs()
+            .allow_non_ascii()
+            .apply("a\tb é");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_input() {
+        let input = Input::read("1 2\r\n3\n\n4".as_bytes()).unwrap();
+        assert_eq!(input.lines().collect::<Vec<_>>(), vec!["1 2", "3", "", "4"]);
+        assert_eq!(input.paragraphs().len(), 2);
+
+        let first = |l: &str| ints::<u8>(l)?.first().copied().ok_or(anyhow!("empty"));
+        let e = input.parse_lines(first).unwrap_err();
+        assert_eq!(e.line, Some(3));
+    }
+
+    #[test]
+    fn test_line_reader() -> Result<()> {
+        let mut r = LineReader::new("\u{feff}a\r\nb\n\nc".as_bytes());
+        let mut lines = Vec::new();
+        while let Some(line) = r.next_line()? {
+            lines.push(line.to_string());
+        }
+        assert_eq!(lines, vec!["a", "b", "", "c"]);
+        assert_eq!(r.line_number(), 4);
+
+        // Errors know which line they came from...
+        let r = LineReader::new("1\n2\nx\n".as_bytes());
+        let mut sum = 0;
+        let e = r
+            .for_each_line(|line| {
+                sum += line.parse::<u32>()?;
+                Ok(())
+            })
+            .unwrap_err();
+        let e = e.downcast::<ParseError>().unwrap();
+        assert_eq!((e.line, e.text.as_str()), (Some(3), "x"));
+        assert_eq!(sum, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_paragraphs() {
+        let text = "seeds: 1 2\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n  \n\nx\n\n";
+        assert_eq!(
+            paragraphs(text),
+            vec![
+                vec!["seeds: 1 2"],
+                vec!["seed-to-soil map:", "50 98 2", "52 50 48"],
+                vec!["x"],
+            ]
+        );
+        assert!(paragraphs("\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_ints() {
+        assert_eq!(ints::<i64>("p=0,4 v=-3,3"), Ok(vec![0, 4, -3, 3]));
+        assert_eq!(ints::<i64>("Time:      7  15   30"), Ok(vec![7, 15, 30]));
+        assert_eq!(ints::<i64>("1-5 -2 a-3"), Ok(vec![1, 5, -2, 3]));
+        assert_eq!(ints::<i64>("no numbers"), Ok(vec![]));
+        assert_eq!(int_strs("-").count(), 0);
+
+        let e = ints::<u8>("1 2 300").unwrap_err();
+        assert_eq!((e.column, e.width), (5, 3));
+    }
+
+    #[test]
+    fn test_columns() {
+        assert_eq!(columns("[Z] [M] [P]", 4), vec!["[Z] ", "[M] ", "[P]"]);
+        assert_eq!(columns("aé", 1), vec!["a", "é"]);
+        assert!(columns("", 3).is_empty());
+    }
+}
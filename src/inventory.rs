@@ -0,0 +1,208 @@
+use crate::error::ParseError;
+use crate::parse::{self, Parser};
+use anyhow::Result;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Add;
+
+/// Counts of cubes (or anything else) keyed by color name.
+///
+/// Colors that aren't present have a count of zero, so
+/// inventories with different sets of colors can still be
+/// combined and compared. Zero counts are never stored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    counts: BTreeMap<String, usize>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a comma-separated list of counts like
+    /// `3 blue, 4 red`. Any color name is accepted, and
+    /// repeated colors are added together.
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(parse::line(s, Self::parse_from)?)
+    }
+
+    /// Parses a list of counts (see [`Inventory::parse`])
+    /// from the middle of a line, stopping after the last
+    /// count.
+    pub fn parse_from(p: &mut Parser) -> Result<Self, ParseError> {
+        let mut inv = Self::new();
+        p.sep_by(",", |p| {
+            p.ws();
+            let count = p.uint()?;
+            p.ws();
+            let color = p.ident()?;
+            p.ws();
+            inv.insert(color, count);
+            Ok(())
+        })?;
+        Ok(inv)
+    }
+
+    /// Adds `count` cubes of `color`.
+    pub fn insert(&mut self, color: &str, count: usize) -> &mut Self {
+        if count > 0 {
+            *self.counts.entry(color.to_string()).or_insert(0) += count;
+        }
+        self
+    }
+
+    /// Returns the number of cubes of `color`.
+    pub fn get(&self, color: &str) -> usize {
+        self.counts.get(color).copied().unwrap_or(0)
+    }
+
+    /// Iterates over the colors (with non-zero counts)
+    /// in alphabetical order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.counts.iter().map(|(c, n)| (c.as_str(), *n))
+    }
+
+    /// The total number of cubes.
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Returns the color-by-color maximum of the two
+    /// inventories (ie the smallest inventory that could
+    /// have produced both).
+    pub fn max(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for (color, &n) in &other.counts {
+            let c = out.counts.entry(color.clone()).or_insert(0);
+            *c = (*c).max(n);
+        }
+        out
+    }
+
+    /// Returns the product of the counts of `colors`, so
+    /// colors that are missing make the power zero.
+    pub fn power_of(&self, colors: &[&str]) -> usize {
+        colors.iter().map(|c| self.get(c)).product()
+    }
+
+    /// Returns the product of every (non-zero) count, or
+    /// zero for an empty inventory.
+    pub fn power(&self) -> usize {
+        if self.counts.is_empty() {
+            return 0;
+        }
+        self.counts.values().product()
+    }
+}
+
+impl Add for &Inventory {
+    type Output = Inventory;
+
+    fn add(self, other: &Inventory) -> Inventory {
+        let mut out = self.clone();
+        for (color, &n) in &other.counts {
+            out.insert(color, n);
+        }
+        out
+    }
+}
+
+/// Inventories are ordered by containment: `a <= b` when
+/// `b` has at least as many cubes of every color as `a`.
+/// Inventories where each has more of some color than the
+/// other aren't comparable.
+impl PartialOrd for Inventory {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let colors = self.counts.keys().chain(other.counts.keys());
+        let (mut less, mut greater) = (false, false);
+        for color in colors {
+            match self.get(color).cmp(&other.get(color)) {
+                Ordering::Less => less = true,
+                Ordering::Greater => greater = true,
+                Ordering::Equal => {}
+            }
+        }
+        match (less, greater) {
+            (false, false) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (true, true) => None,
+        }
+    }
+}
+
+impl fmt::Display for Inventory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts = self
+            .iter()
+            .map(|(c, n)| format!("{} {}", n, c))
+            .collect::<Vec<_>>();
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let inv = Inventory::parse(" 3 blue, 4 red, 1 blue")?;
+        assert_eq!(inv.get("blue"), 4);
+        assert_eq!(inv.get("red"), 4);
+        assert_eq!(inv.get("green"), 0);
+        assert_eq!(inv.to_string(), "4 blue, 4 red");
+
+        // Any color works...
+        let inv = Inventory::parse("2 chartreuse, 0 red")?;
+        assert_eq!(inv.iter().collect::<Vec<_>>(), vec![("chartreuse", 2)]);
+
+        assert!(Inventory::parse("blue").is_err());
+        assert!(Inventory::parse("3").is_err());
+        assert!(Inventory::parse("3 light blue").is_err());
+
+        // Errors point at the problem...
+        let e = Inventory::parse("3 blue, 4 light red").unwrap_err();
+        let e = e.downcast::<ParseError>().unwrap();
+        assert_eq!((e.column, e.width), (17, 3));
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_and_sum() -> Result<()> {
+        let a = Inventory::parse("3 blue, 4 red")?;
+        let b = Inventory::parse("1 red, 2 green, 6 blue")?;
+        assert_eq!(a.max(&b), Inventory::parse("4 red, 2 green, 6 blue")?);
+        assert_eq!(&a + &b, Inventory::parse("5 red, 2 green, 9 blue")?);
+        assert_eq!((&a + &b).total(), 16);
+        Ok(())
+    }
+
+    #[test]
+    fn test_containment() -> Result<()> {
+        let bag = Inventory::parse("12 red, 13 green, 14 blue")?;
+        assert!(Inventory::parse("3 blue, 4 red")? <= bag);
+        assert!(Inventory::parse("12 red, 13 green, 14 blue")? <= bag);
+        assert_eq!(Inventory::parse("20 red, 1 blue")?.partial_cmp(&bag), None);
+        assert_eq!(Inventory::parse("1 purple")?.partial_cmp(&bag), None);
+        assert!(Inventory::parse("20 red, 20 green, 20 blue")? > bag);
+        assert!(Inventory::new() <= bag);
+
+        let a = Inventory::parse("2 red")?;
+        let b = Inventory::parse("2 blue")?;
+        assert_eq!(a.partial_cmp(&b), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_power() -> Result<()> {
+        let inv = Inventory::parse("4 red, 2 green, 6 blue")?;
+        assert_eq!(inv.power(), 48);
+        assert_eq!(inv.power_of(&["red", "green", "blue"]), 48);
+        assert_eq!(inv.power_of(&["red", "purple"]), 0);
+        assert_eq!(Inventory::new().power(), 0);
+        Ok(())
+    }
+}
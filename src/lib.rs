@@ -1,42 +1,172 @@
 use anyhow::{anyhow, Result};
+use error::{AocError, ParseError};
+use input::Normalize;
 use regex::Regex;
+use std::path::{Path, PathBuf};
+
+pub mod camel;
+pub mod dot;
+pub mod error;
+pub mod galaxy;
+pub mod graph;
+pub mod grid;
+pub mod holiday;
+pub mod input;
+pub mod inventory;
+pub mod math;
+pub mod nonogram;
+pub mod parse;
+pub mod runner;
+pub mod scaffold;
+pub mod schematic;
+pub mod scratchcards;
+pub mod sim;
+pub mod text;
+
+/// Checks if the program was run with the command line
+/// flag `flag` (eg `--dot`).
+pub fn has_flag(flag: &str) -> bool {
+    std::env::args().skip(1).any(|a| a == flag)
+}
+
+/// Returns the value following the command line flag
+/// `flag` (eg `--bag "12 red, 13 green"`), if it was given.
+pub fn flag_value(flag: &str) -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    args.find(|a| a == flag)?;
+    args.next()
+}
+
+/// What a solution's answer line starts with, which is how
+/// the runner (see [`runner`]) tells it apart from the rest
+/// of the output.
+pub const ANSWER_PREFIX: &str = "Answer: ";
+
+/// Prints a solution's answer (as `Answer: <answer>`). This
+/// should be the last thing a solution prints.
+pub fn print_answer(answer: impl std::fmt::Display) {
+    println!("{}{}", ANSWER_PREFIX, answer);
+}
+
+/// The directory that debugging output (like `.dot`
+/// files and frame logs) gets written to.
+pub const OUT_DIR: &str = "out";
+
+/// Returns the path `out/<stem><suffix>`, where `<stem>`
+/// is the file stem of `name` (eg `file!()`), creating
+/// the `out/` directory if needed.
+pub fn out_path(name: &str, suffix: &str) -> Result<PathBuf> {
+    let stem = Path::new(name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or(anyhow!("Invalid filename: {}", name))?;
+    std::fs::create_dir_all(OUT_DIR)?;
+    Ok(Path::new(OUT_DIR).join(format!("{}{}", stem, suffix)))
+}
 
 /// Loads the input data for the `d`th day and
-/// returns it as a single, raw `String`.
-pub fn load_input(d: u8) -> Result<String> {
-    let p = format!("data/{:02}.txt", d);
-    let s = std::fs::read_to_string(p)?;
-    Ok(s)
+/// returns it as a single `String` (with the default
+/// [`Normalize`] clean-up).
+pub fn load_input(d: u8) -> Result<String, AocError> {
+    load_input_with(d, Normalize::new())
 }
 
-pub fn load_input_lines(d: u8) -> Result<Vec<String>> {
+/// Loads the input data for the `d`th day, cleaned up
+/// as `normalize` says, printing any warnings to stderr.
+pub fn load_input_with(d: u8, normalize: Normalize) -> Result<String, AocError> {
+    let path = input_path(d);
+    let raw = std::fs::read_to_string(&path).map_err(|e| AocError::read(d, path, e))?;
+    let (text, warnings) = normalize.apply(&raw);
+    for w in warnings {
+        eprintln!("warning: day {}: {}", d, w);
+    }
+    Ok(text)
+}
+
+/// The path to the input data for the `d`th day.
+pub fn input_path(d: u8) -> PathBuf {
+    PathBuf::from(format!("data/{:02}.txt", d))
+}
+
+pub fn load_input_lines(d: u8) -> Result<Vec<String>, AocError> {
     let raw = load_input(d)?;
     let lines = raw.lines().map(|l| l.to_string()).collect::<Vec<_>>();
     Ok(lines)
 }
 
-fn parse_filename(name: &str) -> Result<u8> {
-    let re = Regex::new(r"day(\d{2})-part\d\.rs")?;
-    let caps = re
-        .captures(name)
-        .ok_or(anyhow::anyhow!("Invalid filename: {}", name))?;
-    caps.get(1)
-        .ok_or(anyhow!("Regex didn't match against file \"{}\"", name))?
-        .as_str()
-        .parse::<u8>()
-        .map_err(|_| anyhow!("Failed to parse day from filename \"{}\"", name))
+/// Gets the day from a binary's name (eg `file!()`), which
+/// should look like `dayNN-partM.rs`.
+pub(crate) fn parse_filename(name: &str) -> Result<u8, AocError> {
+    let invalid = || AocError::InvalidDay(name.to_string());
+    let re = Regex::new(r"day(\d{2})-part\d\.rs").expect("valid regex");
+    let caps = re.captures(name).ok_or_else(invalid)?;
+    caps[1].parse::<u8>().map_err(|_| invalid())
 }
 
-pub fn load_input_by_name(name: &str) -> Result<String> {
+pub fn load_input_by_name(name: &str) -> Result<String, AocError> {
     let d = parse_filename(name)?;
     load_input(d)
 }
 
-pub fn load_input_lines_by_name(name: &str) -> Result<Vec<String>> {
+pub fn load_input_by_name_with(name: &str, normalize: Normalize) -> Result<String, AocError> {
+    let d = parse_filename(name)?;
+    load_input_with(d, normalize)
+}
+
+pub fn load_input_lines_by_name(name: &str) -> Result<Vec<String>, AocError> {
     let d = parse_filename(name)?;
     load_input_lines(d)
 }
 
+/// Turns an error from parsing `line` (line `n`, counting
+/// from 1) into a [`ParseError`]. Parse errors about part of
+/// the line are re-anchored to the whole line, and anything
+/// else is reported against the whole line.
+pub fn line_error(e: anyhow::Error, n: usize, line: &str) -> ParseError {
+    match e.downcast::<ParseError>() {
+        Ok(e) => {
+            let text = e.text.clone();
+            e.in_context(line, &text).with_line(n)
+        }
+        Err(e) => ParseError::new(line, format!("{:#}", e)).with_line(n),
+    }
+}
+
+/// Parses each of `lines` with `f`, stopping at the first
+/// error and reporting which line it was on.
+pub fn parse_lines<S, T, F>(lines: &[S], mut f: F) -> Result<Vec<T>, ParseError>
+where
+    S: AsRef<str>,
+    F: FnMut(&str) -> Result<T>,
+{
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| f(line.as_ref()).map_err(|e| line_error(e, i + 1, line.as_ref())))
+        .collect()
+}
+
+/// Loads the input for the binary `name` (eg `file!()`)
+/// and parses it with `f`, adding the day to any
+/// [`ParseError`] it returns.
+pub fn parse_input_by_name<T, F>(name: &str, f: F) -> Result<T, AocError>
+where
+    F: FnOnce(&[String]) -> Result<T>,
+{
+    let d = parse_filename(name)?;
+    let lines = load_input_lines(d)?;
+    f(&lines).map_err(|e| AocError::from(e).with_day(d))
+}
+
+/// Loads the input for the binary `name` (eg `file!()`)
+/// and parses it line by line with `f`.
+pub fn parse_input_lines_by_name<T, F>(name: &str, f: F) -> Result<Vec<T>, AocError>
+where
+    F: FnMut(&str) -> Result<T>,
+{
+    parse_input_by_name(name, |lines| Ok(parse_lines(lines, f)?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -55,6 +185,13 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_out_path() -> Result<()> {
+        let p = out_path("src/bin/day08-part1.rs", ".dot")?;
+        assert_eq!(p, Path::new("out/day08-part1.dot"));
+        Ok(())
+    }
+
     #[test]
     fn test_parse_filename() -> Result<()> {
         assert_eq!(parse_filename("day01-part1.rs")?, 1);
@@ -65,4 +202,43 @@ mod tests {
         assert_eq!(parse_filename("./bar/baz/day01-part1.rs")?, 1);
         Ok(())
     }
+
+    #[test]
+    fn test_input_errors() {
+        let e = load_input(99).unwrap_err();
+        assert!(matches!(e, AocError::MissingInput { day: 99, .. }));
+        let e = load_input_by_name("src/bin/main.rs").unwrap_err();
+        assert!(matches!(e, AocError::InvalidDay(_)));
+
+        // Parse errors get the day, anything else is the
+        // solution's problem...
+        let e = parse_input_lines_by_name("day01-part1.rs", |line| {
+            Err::<(), _>(ParseError::new(line, "bad").into())
+        })
+        .unwrap_err();
+        assert!(matches!(e, AocError::Parse(ParseError { day: Some(1), line: Some(1), .. })));
+        let e = parse_input_by_name("day01-part1.rs", |_| Err::<(), _>(anyhow!("nope")));
+        assert!(matches!(e, Err(AocError::Solution(_))));
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let lines = ["1 2", "3 x", "5"];
+        let parse = |line: &str| {
+            line.split_whitespace()
+                .map(|n| {
+                    n.parse::<u32>()
+                        .map_err(|_| ParseError::within(line, n, "expected a number").into())
+                })
+                .collect::<Result<Vec<_>>>()
+        };
+        let e = parse_lines(&lines, parse).unwrap_err();
+        assert_eq!((e.line, e.column, e.width), (Some(2), 3, 1));
+        assert_eq!(e.text, "3 x");
+
+        // Other errors cover the whole line...
+        let e = parse_lines(&lines, |_| Err::<(), _>(anyhow!("nope"))).unwrap_err();
+        assert_eq!((e.line, e.column, e.width), (Some(1), 1, 3));
+        assert_eq!(e.message, "nope");
+    }
 }
@@ -0,0 +1,88 @@
+/// The greatest common divisor of `a` and `b`.
+pub fn gcd(a: u64, b: u64) -> u64 {
+    let (mut a, mut b) = (a, b);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// The least common multiple of all the numbers (1 for
+/// an empty list), or `None` if it overflows.
+pub fn lcm(ns: &[u64]) -> Option<u64> {
+    ns.iter().try_fold(1u64, |acc, &n| {
+        if n == 0 {
+            return Some(0);
+        }
+        (acc / gcd(acc, n)).checked_mul(n)
+    })
+}
+
+/// Returns `(g, x, y)` where `a*x + b*y == g == gcd(a, b)`.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - (a / b) * y)
+    }
+}
+
+/// Solves a system of congruences `t ≡ a (mod n)` with
+/// the Chinese remainder theorem. The moduli don't need to
+/// be coprime.
+///
+/// Returns the smallest non-negative solution along with
+/// the modulus it repeats at (the LCM of the moduli), or
+/// `None` if there's no solution (or it overflows).
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    let mut a: i128 = 0;
+    let mut n: i128 = 1;
+    for &(b, m) in congruences {
+        if m == 0 {
+            return None;
+        }
+        let (b, m) = (b as i128 % m as i128, m as i128);
+
+        // Find k such that a + n*k ≡ b (mod m)...
+        let (g, x, _) = extended_gcd(n, m);
+        if (b - a) % g != 0 {
+            return None;
+        }
+        let step = m / g;
+        let k = ((b - a) / g % step).checked_mul(x % step)? % step;
+        a += n.checked_mul(k)?;
+        n = n.checked_mul(step)?;
+        a = a.rem_euclid(n);
+        if n > u64::MAX as i128 {
+            return None;
+        }
+    }
+    Some((a as u64, n as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(7, 0), 7);
+        assert_eq!(lcm(&[4, 6, 10]), Some(60));
+        assert_eq!(lcm(&[]), Some(1));
+        assert_eq!(lcm(&[u64::MAX, 2]), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+
+        // Moduli that share factors...
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(0, 4), (1, 6)]), None);
+
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(5, 3)]), Some((2, 3)));
+    }
+}
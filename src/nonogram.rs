@@ -0,0 +1,509 @@
+use anyhow::{anyhow, Context, Result};
+use std::collections::VecDeque;
+use std::fmt;
+
+/// A cell in a nonogram line (or a spring in day 12).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Empty,
+    Filled,
+    Unknown,
+}
+
+impl From<Cell> for char {
+    fn from(c: Cell) -> char {
+        match c {
+            Cell::Empty => '.',
+            Cell::Filled => '#',
+            Cell::Unknown => '?',
+        }
+    }
+}
+
+/// A line's cells, with a running count of the empty ones
+/// so that checking where a run fits takes one step.
+struct Line<'a> {
+    cells: &'a [Cell],
+
+    /// `empties[i]` is the number of empty cells before `i`.
+    empties: Vec<usize>,
+}
+
+impl<'a> Line<'a> {
+    fn new(cells: &'a [Cell]) -> Self {
+        let mut empties = vec![0; cells.len() + 1];
+        for (i, &c) in cells.iter().enumerate() {
+            empties[i + 1] = empties[i] + usize::from(c == Cell::Empty);
+        }
+        Self { cells, empties }
+    }
+
+    /// Checks if a run of `len` filled cells can start at
+    /// `i`: none of the cells are empty, and the cell after
+    /// the run (if any) isn't filled.
+    fn run_fits(&self, i: usize, len: usize) -> bool {
+        let end = i + len;
+        end <= self.cells.len()
+            && self.empties[end] == self.empties[i]
+            && self.cells.get(end) != Some(&Cell::Filled)
+    }
+
+    /// Where a run of `len` starting at `i` leaves us: past
+    /// the run and the empty cell after it.
+    fn after_run(&self, i: usize, len: usize) -> usize {
+        (i + len + 1).min(self.cells.len())
+    }
+}
+
+/// Counts the ways the unknown cells in a line can be
+/// filled in so its runs of filled cells match `clue`.
+///
+/// `ways[j][i]` is the number of ways to finish the line
+/// from cell `i` using runs `j..`, where cell `i` is free
+/// to start a run. That makes this O(cells * runs), with
+/// each run checked in one step (see `Line::run_fits`).
+pub fn count_arrangements(cells: &[Cell], clue: &[usize]) -> u64 {
+    let (n, k) = (cells.len(), clue.len());
+    let line = Line::new(cells);
+    let mut ways = vec![vec![0u64; n + 1]; k + 1];
+
+    // With no runs left, the rest has to be empty...
+    ways[k][n] = 1;
+    for i in (0..n).rev() {
+        ways[k][i] = if cells[i] == Cell::Filled {
+            0
+        } else {
+            ways[k][i + 1]
+        };
+    }
+
+    for j in (0..k).rev() {
+        for i in (0..n).rev() {
+            let mut w = 0;
+            if cells[i] != Cell::Filled {
+                w += ways[j][i + 1];
+            }
+            if line.run_fits(i, clue[j]) {
+                w += ways[j + 1][line.after_run(i, clue[j])];
+            }
+            ways[j][i] = w;
+        }
+    }
+    ways[0][0]
+}
+
+/// Works out every cell in the line that's the same in all
+/// of its arrangements, or `None` if there aren't any.
+///
+/// Each step (leave a cell empty, or place the next run)
+/// is kept if it can be reached from the start and can
+/// still reach the end. Every cell a kept step sets is a
+/// possibility for that cell.
+pub fn solve_line(cells: &[Cell], clue: &[usize]) -> Option<Vec<Cell>> {
+    let (n, k) = (cells.len(), clue.len());
+    let line = Line::new(cells);
+
+    // Which states can finish the line...
+    let mut done = vec![vec![false; n + 1]; k + 1];
+    done[k][n] = true;
+    for j in (0..=k).rev() {
+        for i in (0..n).rev() {
+            done[j][i] = (cells[i] != Cell::Filled && done[j][i + 1])
+                || (j < k && line.run_fits(i, clue[j]) && done[j + 1][line.after_run(i, clue[j])]);
+        }
+    }
+    if !done[0][0] {
+        return None;
+    }
+
+    // Walk forward from the start, only through states
+    // that can finish, recording what each cell can be...
+    let mut reached = vec![vec![false; n + 1]; k + 1];
+    reached[0][0] = true;
+    let mut can_empty = vec![false; n];
+    let mut filled_delta = vec![0i32; n + 1];
+    for i in 0..n {
+        for j in 0..=k {
+            if !reached[j][i] {
+                continue;
+            }
+            if cells[i] != Cell::Filled && done[j][i + 1] {
+                reached[j][i + 1] = true;
+                can_empty[i] = true;
+            }
+            if j < k && line.run_fits(i, clue[j]) {
+                let next = line.after_run(i, clue[j]);
+                if done[j + 1][next] {
+                    reached[j + 1][next] = true;
+                    filled_delta[i] += 1;
+                    filled_delta[i + clue[j]] -= 1;
+                    if i + clue[j] < n {
+                        can_empty[i + clue[j]] = true;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut filled = 0;
+    let solved = (0..n)
+        .map(|i| {
+            filled += filled_delta[i];
+            match (filled > 0, can_empty[i]) {
+                (true, false) => Cell::Filled,
+                (false, true) => Cell::Empty,
+                _ => Cell::Unknown,
+            }
+        })
+        .collect();
+    Some(solved)
+}
+
+/// A (possibly partly) filled-in nonogram grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Board {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            rows: vec![vec![Cell::Unknown; width]; height],
+        }
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Cell {
+        self.rows[row][col]
+    }
+
+    pub fn row(&self, row: usize) -> &[Cell] {
+        &self.rows[row]
+    }
+
+    pub fn col(&self, col: usize) -> Vec<Cell> {
+        self.rows.iter().map(|r| r[col]).collect()
+    }
+
+    /// Returns the first cell that's still unknown.
+    fn first_unknown(&self) -> Option<(usize, usize)> {
+        self.rows
+            .iter()
+            .enumerate()
+            .find_map(|(r, row)| row.iter().position(|&c| c == Cell::Unknown).map(|c| (r, c)))
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.first_unknown().is_none()
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in &self.rows {
+            writeln!(
+                f,
+                "{}",
+                row.iter().map(|&c| char::from(c)).collect::<String>()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of solving a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    None,
+    Unique(Board),
+
+    /// The puzzle has (at least) two solutions. These are
+    /// the first two found.
+    Multiple(Board, Board),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineId {
+    Row(usize),
+    Col(usize),
+}
+
+/// A nonogram puzzle: the clues for each row and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    rows: Vec<Vec<usize>>,
+    cols: Vec<Vec<usize>>,
+}
+
+impl Puzzle {
+    pub fn new(rows: Vec<Vec<usize>>, cols: Vec<Vec<usize>>) -> Result<Self> {
+        let fits = |clue: &[usize], len: usize| {
+            clue.iter().sum::<usize>() + clue.len().saturating_sub(1) <= len
+        };
+        if let Some(i) = rows.iter().position(|c| !fits(c, cols.len())) {
+            return Err(anyhow!(
+                "Row {} clue doesn't fit in {} columns",
+                i + 1,
+                cols.len()
+            ));
+        }
+        if let Some(i) = cols.iter().position(|c| !fits(c, rows.len())) {
+            return Err(anyhow!(
+                "Column {} clue doesn't fit in {} rows",
+                i + 1,
+                rows.len()
+            ));
+        }
+        Ok(Self { rows, cols })
+    }
+
+    /// Parses a clue file. It has a `rows:` section and a
+    /// `columns:` section, each with one clue per line (in
+    /// order), with run lengths separated by spaces or
+    /// commas. An empty line's clue is `0` or `-`. Anything
+    /// after a `;` is a comment.
+    ///
+    /// ```text
+    /// rows:
+    /// 1 1
+    /// -
+    /// columns:
+    /// 1
+    /// 0
+    /// 1
+    /// ```
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rows = Vec::new();
+        let mut cols = Vec::new();
+        let mut section: Option<&mut Vec<Vec<usize>>> = None;
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            match line.to_lowercase().as_str() {
+                "rows:" => {
+                    section = Some(&mut rows);
+                    continue;
+                }
+                "columns:" | "cols:" => {
+                    section = Some(&mut cols);
+                    continue;
+                }
+                _ => {}
+            }
+            let clues = section.as_mut().ok_or(anyhow!(
+                "Clue on line {} before \"rows:\" or \"columns:\"",
+                i + 1
+            ))?;
+            let clue = line
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty() && *s != "-")
+                .map(|s| s.parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()
+                .with_context(|| format!("Invalid clue on line {}: \"{}\"", i + 1, line))?;
+            clues.push(clue.into_iter().filter(|&n| n > 0).collect());
+        }
+        if rows.is_empty() || cols.is_empty() {
+            return Err(anyhow!("Puzzle needs both rows and columns"));
+        }
+        Self::new(rows, cols)
+    }
+
+    pub fn width(&self) -> usize {
+        self.cols.len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Fills in everything that follows from the clues one
+    /// line at a time, re-checking lines that cross any
+    /// cell that changed. Returns `false` on a contradiction.
+    fn propagate(&self, board: &mut Board, dirty: impl IntoIterator<Item = LineId>) -> bool {
+        let mut queue = dirty.into_iter().collect::<VecDeque<_>>();
+        let mut queued_rows = vec![false; self.height()];
+        let mut queued_cols = vec![false; self.width()];
+        for line in &queue {
+            match *line {
+                LineId::Row(r) => queued_rows[r] = true,
+                LineId::Col(c) => queued_cols[c] = true,
+            }
+        }
+
+        while let Some(line) = queue.pop_front() {
+            match line {
+                LineId::Row(r) => {
+                    queued_rows[r] = false;
+                    let Some(solved) = solve_line(board.row(r), &self.rows[r]) else {
+                        return false;
+                    };
+                    for (c, cell) in solved.into_iter().enumerate() {
+                        if cell != board.rows[r][c] {
+                            board.rows[r][c] = cell;
+                            if !queued_cols[c] {
+                                queued_cols[c] = true;
+                                queue.push_back(LineId::Col(c));
+                            }
+                        }
+                    }
+                }
+                LineId::Col(c) => {
+                    queued_cols[c] = false;
+                    let Some(solved) = solve_line(&board.col(c), &self.cols[c]) else {
+                        return false;
+                    };
+                    for (r, cell) in solved.into_iter().enumerate() {
+                        if cell != board.rows[r][c] {
+                            board.rows[r][c] = cell;
+                            if !queued_rows[r] {
+                                queued_rows[r] = true;
+                                queue.push_back(LineId::Row(r));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Propagates, then guesses the first unknown cell
+    /// both ways, stopping once `limit` solutions are found.
+    fn search(&self, mut board: Board, dirty: Vec<LineId>, found: &mut Vec<Board>, limit: usize) {
+        if found.len() >= limit || !self.propagate(&mut board, dirty) {
+            return;
+        }
+        let Some((r, c)) = board.first_unknown() else {
+            found.push(board);
+            return;
+        };
+        for guess in [Cell::Filled, Cell::Empty] {
+            let mut next = board.clone();
+            next.rows[r][c] = guess;
+            self.search(next, vec![LineId::Row(r), LineId::Col(c)], found, limit);
+        }
+    }
+
+    /// Solves the puzzle, checking whether the solution is
+    /// unique.
+    pub fn solve(&self) -> Solution {
+        let board = Board::new(self.width(), self.height());
+        let all = (0..self.height())
+            .map(LineId::Row)
+            .chain((0..self.width()).map(LineId::Col))
+            .collect();
+        let mut found = Vec::new();
+        self.search(board, all, &mut found, 2);
+
+        let mut found = found.into_iter();
+        match (found.next(), found.next()) {
+            (None, _) => Solution::None,
+            (Some(a), None) => Solution::Unique(a),
+            (Some(a), Some(b)) => Solution::Multiple(a, b),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cells(s: &str) -> Vec<Cell> {
+        s.chars()
+            .map(|c| match c {
+                '.' => Cell::Empty,
+                '#' => Cell::Filled,
+                _ => Cell::Unknown,
+            })
+            .collect()
+    }
+
+    fn text(cells: &[Cell]) -> String {
+        cells.iter().map(|&c| char::from(c)).collect()
+    }
+
+    #[test]
+    fn test_run_fits() {
+        let c = cells("?#.??#");
+        let line = Line::new(&c);
+        assert!(line.run_fits(0, 2));
+        assert!(!line.run_fits(0, 3));
+        assert!(!line.run_fits(0, 1));
+        assert!(line.run_fits(3, 3));
+        assert!(!line.run_fits(4, 3));
+        assert!(!line.run_fits(3, 2));
+        assert_eq!(line.after_run(3, 3), 6);
+    }
+
+    #[test]
+    fn test_count_arrangements() {
+        let cases = [
+            ("???.###", vec![1, 1, 3], 1),
+            (".??..??...?##.", vec![1, 1, 3], 4),
+            ("?#?#?#?#?#?#?#?", vec![1, 3, 1, 6], 1),
+            ("????.#...#...", vec![4, 1, 1], 1),
+            ("????.######..#####.", vec![1, 6, 5], 4),
+            ("?###????????", vec![3, 2, 1], 10),
+            ("???", vec![], 1),
+            ("#??", vec![], 0),
+        ];
+        for (line, clue, expected) in cases {
+            assert_eq!(
+                count_arrangements(&cells(line), &clue),
+                expected,
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_solve_line() {
+        let solve = |line, clue: &[usize]| solve_line(&cells(line), clue).map(|c| text(&c));
+        assert_eq!(solve("??????????", &[8]), Some("??######??".to_string()));
+        assert_eq!(solve("?????", &[1, 1, 1]), Some("#.#.#".to_string()));
+        assert_eq!(solve("?#???", &[3]), Some("?##?.".to_string()));
+        assert_eq!(solve("???", &[]), Some("...".to_string()));
+        assert_eq!(solve("?.?", &[2]), None);
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let p = Puzzle::parse("; A tiny puzzle\nrows:\n1 1\n-\ncolumns:\n1\n0\n1, 0\n")?;
+        assert_eq!(p.rows, vec![vec![1, 1], vec![]]);
+        assert_eq!(p.cols, vec![vec![1], vec![], vec![1]]);
+        assert!(Puzzle::parse("1 1\nrows:\n1").is_err());
+        assert!(Puzzle::parse("rows:\nx\ncolumns:\n1").is_err());
+        assert!(Puzzle::parse("rows:\n3\ncolumns:\n1\n1").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_unique() -> Result<()> {
+        // A little heart...
+        let p = Puzzle::parse("rows:\n1 1\n5\n5\n3\n1\ncolumns:\n2\n4\n4\n4\n2\n")?;
+        let Solution::Unique(board) = p.solve() else {
+            panic!("Expected a unique solution");
+        };
+        assert_eq!(board.to_string(), ".#.#.\n#####\n#####\n.###.\n..#..\n");
+        assert!(board.is_solved());
+        Ok(())
+    }
+
+    #[test]
+    fn test_needs_backtracking() -> Result<()> {
+        // Line solving alone gets nowhere with a diagonal,
+        // and this one has two solutions...
+        let p = Puzzle::parse("rows:\n1\n1\ncolumns:\n1\n1\n")?;
+        let Solution::Multiple(a, b) = p.solve() else {
+            panic!("Expected two solutions");
+        };
+        assert_ne!(a, b);
+
+        // No solution at all...
+        let p = Puzzle::parse("rows:\n2\n0\ncolumns:\n0\n1\n")?;
+        assert_eq!(p.solve(), Solution::None);
+        Ok(())
+    }
+}
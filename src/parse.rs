@@ -0,0 +1,274 @@
+use crate::error::ParseError;
+use std::str::FromStr;
+
+/// A small parser over a single line of input.
+///
+/// Each method consumes what it recognizes and returns a
+/// typed value, or a [`ParseError`] pointing at where it
+/// got stuck. Methods that take a parser function (like
+/// [`Parser::sep_by`]) combine smaller parsers into bigger
+/// ones:
+///
+/// ```
+/// use aoc23::parse::{self, Parser};
+///
+/// let (id, nums) = parse::line("Card 1: 41 48 83", |p| {
+///     p.literal("Card")?;
+///     p.ws();
+///     let id = p.uint::<usize>()?;
+///     p.literal(":")?;
+///     let nums = p.many(|p| {
+///         p.ws();
+///         p.uint::<usize>()
+///     })?;
+///     Ok((id, nums))
+/// })
+/// .unwrap();
+/// assert_eq!((id, nums), (1, vec![41, 48, 83]));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+/// Parses the whole of `text` with `f`, failing if there's
+/// anything left over.
+pub fn line<'a, T, F>(text: &'a str, f: F) -> Result<T, ParseError>
+where
+    F: FnOnce(&mut Parser<'a>) -> Result<T, ParseError>,
+{
+    let mut p = Parser::new(text);
+    let value = f(&mut p)?;
+    p.end()?;
+    Ok(value)
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(text: &'a str) -> Self {
+        Self { text, pos: 0 }
+    }
+
+    /// The text that hasn't been parsed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    /// The (1-based) column of the next character.
+    pub fn column(&self) -> usize {
+        self.text[..self.pos].chars().count() + 1
+    }
+
+    /// An error at the current position, underlining the
+    /// next word (if there is one).
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        let word = self.rest().split_whitespace().next().unwrap_or("");
+        ParseError::span(self.text, self.column(), word.chars().count(), message)
+    }
+
+    /// Takes the (possibly empty) run of characters matching
+    /// `pred` from the front of the rest of the text.
+    pub fn take_while(&mut self, pred: impl Fn(char) -> bool) -> &'a str {
+        let rest = self.rest();
+        let len = rest.find(|c| !pred(c)).unwrap_or(rest.len());
+        self.pos += len;
+        &rest[..len]
+    }
+
+    /// Skips any spaces or tabs.
+    pub fn ws(&mut self) {
+        self.take_while(|c| c == ' ' || c == '\t');
+    }
+
+    /// Consumes `lit` if it's next, returning whether it was.
+    pub fn eat(&mut self, lit: &str) -> bool {
+        let found = self.rest().starts_with(lit);
+        if found {
+            self.pos += lit.len();
+        }
+        found
+    }
+
+    /// Expects `lit` to be next.
+    pub fn literal(&mut self, lit: &str) -> Result<(), ParseError> {
+        if self.eat(lit) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected \"{}\"", lit)))
+        }
+    }
+
+    /// Parses a run of digits as a `T`.
+    pub fn uint<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = self.pos;
+        self.take_while(|c| c.is_ascii_digit());
+        self.number(start)
+    }
+
+    /// Parses a run of digits, with an optional sign, as a
+    /// `T`.
+    pub fn int<T: FromStr>(&mut self) -> Result<T, ParseError> {
+        let start = self.pos;
+        let _ = self.eat("-") || self.eat("+");
+        self.take_while(|c| c.is_ascii_digit());
+        self.number(start)
+    }
+
+    /// Parses the text from `start` as a number.
+    fn number<T: FromStr>(&mut self, start: usize) -> Result<T, ParseError> {
+        let text = &self.text[start..self.pos];
+        if !text.ends_with(|c: char| c.is_ascii_digit()) {
+            self.pos = start;
+            return Err(self.error("expected a number"));
+        }
+        text.parse::<T>().map_err(|_| {
+            let column = self.text[..start].chars().count() + 1;
+            ParseError::span(self.text, column, text.len(), "number out of range")
+        })
+    }
+
+    /// Parses an identifier: a run of letters, digits and
+    /// underscores.
+    pub fn ident(&mut self) -> Result<&'a str, ParseError> {
+        let id = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if id.is_empty() {
+            Err(self.error("expected a name"))
+        } else {
+            Ok(id)
+        }
+    }
+
+    /// Tries `item`, putting things back the way they were
+    /// (and returning `None`) if it fails.
+    pub fn optional<T, F>(&mut self, item: F) -> Option<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
+    {
+        let start = self.pos;
+        let value = item(self).ok();
+        if value.is_none() {
+            self.pos = start;
+        }
+        value
+    }
+
+    /// Parses as many `item`s as it can (maybe none). It also
+    /// stops at an `item` that doesn't consume anything (which
+    /// would otherwise match forever), leaving that one out.
+    pub fn many<T, F>(&mut self, mut item: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        let mut items = Vec::new();
+        loop {
+            let start = self.pos;
+            match self.optional(&mut item) {
+                Some(value) if self.pos > start => items.push(value),
+                _ => break,
+            }
+        }
+        Ok(items)
+    }
+
+    /// Parses one or more `item`s separated by `sep`.
+    pub fn sep_by<T, F>(&mut self, sep: &str, mut item: F) -> Result<Vec<T>, ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, ParseError>,
+    {
+        let mut items = vec![item(self)?];
+        while self.eat(sep) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    /// Expects there to be nothing left (but whitespace).
+    pub fn end(&mut self) -> Result<(), ParseError> {
+        self.ws();
+        if self.is_done() {
+            Ok(())
+        } else {
+            Err(self.error("unexpected text"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_numbers() {
+        let mut p = Parser::new("12 -3 +4 x 99999");
+        assert_eq!(p.uint::<u32>(), Ok(12));
+        p.ws();
+        assert_eq!(p.int::<i32>(), Ok(-3));
+        p.ws();
+        assert_eq!(p.int::<i32>(), Ok(4));
+        p.ws();
+        assert_eq!(p.uint::<u32>().unwrap_err().column, 10);
+        assert_eq!(p.int::<i32>().unwrap_err().column, 10);
+        assert_eq!(p.column(), 10);
+        p.literal("x ").unwrap();
+        let e = p.uint::<u8>().unwrap_err();
+        assert_eq!((e.column, e.width), (12, 5));
+        assert_eq!(e.message, "number out of range");
+    }
+
+    #[test]
+    fn test_node() {
+        // Day 8's node lines...
+        let node = |s| {
+            line(s, |p| {
+                let id = p.ident()?;
+                p.literal(" = (")?;
+                let left = p.ident()?;
+                p.literal(", ")?;
+                let right = p.ident()?;
+                p.literal(")")?;
+                Ok((id, left, right))
+            })
+        };
+        assert_eq!(node("AAA = (BBB, 11Z)"), Ok(("AAA", "BBB", "11Z")));
+
+        let e = node("AAA = (BBB CCC)").unwrap_err();
+        assert_eq!((e.column, e.message.as_str()), (11, "expected \", \""));
+        let e = node("AAA = (BBB, CCC) !").unwrap_err();
+        assert_eq!((e.column, e.message.as_str()), (18, "unexpected text"));
+    }
+
+    #[test]
+    fn test_lists() {
+        let list = |s| line(s, |p| p.sep_by(",", |p| p.int::<i64>()));
+        assert_eq!(list("1,-2,3"), Ok(vec![1, -2, 3]));
+        assert_eq!(list("1,2,").unwrap_err().column, 5);
+        assert_eq!(list("").unwrap_err().message, "expected a number");
+
+        // `many` stops (without consuming anything) at the
+        // first thing it can't parse...
+        let mut p = Parser::new("1 2 3 | 4");
+        let nums = p.many(|p| {
+            p.ws();
+            p.uint::<u8>()
+        });
+        assert_eq!(nums, Ok(vec![1, 2, 3]));
+        assert_eq!(p.rest(), " | 4");
+        assert_eq!(p.optional(|p| p.literal("|")), None);
+        assert!(p.eat(" |"));
+    }
+
+    #[test]
+    fn test_many_empty_items() {
+        // Items that can match nothing don't loop forever...
+        let mut p = Parser::new("ab12");
+        let words = p.many(|p| Ok(p.take_while(char::is_alphabetic)));
+        assert_eq!(words, Ok(vec!["ab"]));
+        assert_eq!(p.rest(), "12");
+        let more = p.many(|p| Ok(p.take_while(char::is_alphabetic)));
+        assert_eq!(more, Ok(vec![]));
+    }
+}
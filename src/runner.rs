@@ -0,0 +1,424 @@
+use crate::ANSWER_PREFIX;
+use anyhow::{anyhow, Result};
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// One part of one day's puzzle, solved by the binary
+/// `dayNN-partM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Solution {
+    pub day: u8,
+    pub part: u8,
+}
+
+impl Solution {
+    pub const fn new(day: u8, part: u8) -> Self {
+        Self { day, part }
+    }
+
+    /// The name of the solution's binary (eg `day01-part2`).
+    pub fn bin_name(&self) -> String {
+        format!("day{:02}-part{}", self.day, self.part)
+    }
+}
+
+/// Every solution the runner knows about, in day order.
+pub const SOLUTIONS: &[Solution] = &[
+    Solution::new(1, 1),
+    Solution::new(1, 2),
+    Solution::new(2, 1),
+    Solution::new(2, 2),
+    Solution::new(3, 1),
+    Solution::new(3, 2),
+    Solution::new(4, 1),
+    Solution::new(4, 2),
+    Solution::new(5, 1),
+    Solution::new(5, 2),
+    Solution::new(6, 1),
+    Solution::new(6, 2),
+    Solution::new(7, 1),
+    Solution::new(7, 2),
+    Solution::new(8, 1),
+    Solution::new(8, 2),
+    Solution::new(9, 1),
+    Solution::new(9, 2),
+    Solution::new(10, 1),
+    Solution::new(10, 2),
+    Solution::new(11, 1),
+    Solution::new(11, 2),
+    Solution::new(12, 1),
+    Solution::new(13, 1),
+    Solution::new(14, 1),
+    Solution::new(15, 1),
+    Solution::new(15, 2),
+    Solution::new(16, 1),
+    Solution::new(16, 2),
+    Solution::new(17, 1),
+    Solution::new(18, 1),
+    Solution::new(25, 1),
+];
+
+/// How a solution's run went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+
+    /// The solution couldn't be started or exited with an
+    /// error.
+    Error(String),
+
+    /// The solution panicked (with this message).
+    Panic(String),
+
+    /// The solution was killed for taking too long.
+    Timeout,
+}
+
+impl Status {
+    /// A short label for the status column.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Status::Ok => "OK",
+            Status::Error(_) => "ERROR",
+            Status::Panic(_) => "PANIC",
+            Status::Timeout => "TIMEOUT",
+        }
+    }
+}
+
+/// The result of running a single solution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome {
+    pub solution: Solution,
+    pub status: Status,
+
+    /// The answer the solution printed (with
+    /// [`crate::print_answer`]), if it got that far.
+    pub answer: Option<String>,
+
+    pub elapsed: Duration,
+}
+
+impl Outcome {
+    /// Formats the outcome as a row of the results table.
+    pub fn row(&self) -> String {
+        let detail = match &self.status {
+            Status::Ok => self.answer.clone().unwrap_or_default(),
+            Status::Error(msg) | Status::Panic(msg) => msg.clone(),
+            Status::Timeout => format!("Killed after {:.1?}", self.elapsed),
+        };
+        format!(
+            "{:>3}  {:>4}  {:<7}  {:>10}  {}",
+            self.solution.day,
+            self.solution.part,
+            self.status.label(),
+            format!("{:.1?}", self.elapsed),
+            detail
+        )
+    }
+}
+
+/// The header for [`Outcome::row`]s.
+pub fn header() -> String {
+    format!(
+        "{:>3}  {:>4}  {:<7}  {:>10}  {}",
+        "Day", "Part", "Status", "Time", "Answer"
+    )
+}
+
+/// Returns the last non-blank line of `output`.
+fn last_line(output: &str) -> Option<String> {
+    output
+        .lines()
+        .rev()
+        .find(|l| !l.trim().is_empty())
+        .map(|l| l.trim().to_string())
+}
+
+/// Finds the answer in a solution's output: the last line
+/// printed by [`crate::print_answer`].
+fn find_answer(output: &str) -> Option<String> {
+    output
+        .lines()
+        .rev()
+        .find_map(|l| l.trim().strip_prefix(ANSWER_PREFIX))
+        .map(|a| a.trim().to_string())
+}
+
+/// Finds the message in a Rust panic's output, which comes
+/// on the line after "thread 'main' panicked at <location>:".
+fn panic_message(stderr: &str) -> Option<String> {
+    let mut lines = stderr.lines();
+    lines.find(|l| l.contains("panicked at"))?;
+    lines.next().map(|l| l.trim().to_string())
+}
+
+/// Reads all of `r` on a new thread, so a chatty child
+/// can't fill up its pipe and stall while we wait on it.
+fn read_all(mut r: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = r.read_to_end(&mut buf);
+        String::from_utf8_lossy(&buf).into_owned()
+    })
+}
+
+/// Runs `cmd` until it exits or `timeout` passes (at which
+/// point it's killed), returning its status and answer.
+fn run_command(cmd: &mut Command, timeout: Option<Duration>) -> (Status, Option<String>) {
+    let mut child = match cmd.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn() {
+        Ok(child) => child,
+        Err(e) => return (Status::Error(e.to_string()), None),
+    };
+    let stdout = child.stdout.take().map(read_all);
+    let stderr = child.stderr.take().map(read_all);
+
+    // Wait for it to finish, or run out of time...
+    let start = Instant::now();
+    let exit: Option<ExitStatus> = loop {
+        match child.try_wait() {
+            Ok(Some(exit)) => break Some(exit),
+            Ok(None) if timeout.is_some_and(|t| start.elapsed() >= t) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                break None;
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(5)),
+            Err(e) => return (Status::Error(e.to_string()), None),
+        }
+    };
+    let join =
+        |h: Option<thread::JoinHandle<String>>| h.and_then(|h| h.join().ok()).unwrap_or_default();
+    let (stdout, stderr) = (join(stdout), join(stderr));
+    let answer = find_answer(&stdout);
+
+    // (Rust exits with 101 when the main thread panics)...
+    let status = match exit {
+        None => Status::Timeout,
+        Some(exit) if exit.success() => Status::Ok,
+        Some(exit) if exit.code() == Some(101) => {
+            let msg = panic_message(&stderr).unwrap_or_else(|| "Panicked".to_string());
+            Status::Panic(msg)
+        }
+        Some(exit) => Status::Error(last_line(&stderr).unwrap_or_else(|| exit.to_string())),
+    };
+    (status, answer)
+}
+
+/// Runs `f` on each of the jobs using `workers` threads.
+///
+/// `emit` gets the results in the same order as `jobs` (no
+/// matter what order they finish in), each one as soon as
+/// it and every job before it are done.
+pub fn run_ordered<T, R, F, E>(jobs: Vec<T>, workers: usize, f: F, mut emit: E)
+where
+    T: Send,
+    R: Send,
+    F: Fn(T) -> R + Sync,
+    E: FnMut(R),
+{
+    let n = jobs.len();
+    let queue = Mutex::new(jobs.into_iter().enumerate());
+    let (tx, rx) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, n.max(1)) {
+            let tx = tx.clone();
+            let (queue, f) = (&queue, &f);
+            scope.spawn(move || loop {
+                // (Take the next job, releasing the lock
+                // before running it)...
+                let next = queue.lock().ok().and_then(|mut q| q.next());
+                let Some((i, job)) = next else {
+                    break;
+                };
+                if tx.send((i, f(job))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        // Hold on to results that finish early until
+        // everything before them is done...
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (i, r) in rx {
+            pending.insert(i, r);
+            while let Some(r) = pending.remove(&next) {
+                emit(r);
+                next += 1;
+            }
+        }
+    });
+}
+
+/// Runs solution binaries from a directory (normally the
+/// one the runner itself lives in).
+#[derive(Debug, Clone)]
+pub struct Runner {
+    bin_dir: PathBuf,
+    timeout: Option<Duration>,
+}
+
+impl Runner {
+    pub fn new(bin_dir: &Path) -> Self {
+        Self {
+            bin_dir: bin_dir.to_path_buf(),
+            timeout: None,
+        }
+    }
+
+    /// Kills any solution that runs for longer than
+    /// `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Creates a runner for the binaries next to the
+    /// current executable.
+    pub fn from_current_exe() -> Result<Self> {
+        let exe = std::env::current_exe()?;
+        let dir = exe
+            .parent()
+            .ok_or(anyhow!("Can't find the directory of {}", exe.display()))?;
+        Ok(Self::new(dir))
+    }
+
+    fn bin_path(&self, s: Solution) -> PathBuf {
+        self.bin_dir
+            .join(s.bin_name())
+            .with_extension(std::env::consts::EXE_EXTENSION)
+    }
+
+    /// Runs a single solution in its own process, waiting for
+    /// it to finish (or time out). A solution that panics or
+    /// hangs only affects its own outcome.
+    pub fn run(&self, s: Solution) -> Outcome {
+        let start = Instant::now();
+        let path = self.bin_path(s);
+        let (status, answer) = if path.exists() {
+            run_command(&mut Command::new(&path), self.timeout)
+        } else {
+            let msg = format!("{} isn't built (run `cargo build --bins`)", s.bin_name());
+            (Status::Error(msg), None)
+        };
+        Outcome {
+            solution: s,
+            status,
+            answer,
+            elapsed: start.elapsed(),
+        }
+    }
+
+    /// Runs the solutions on `workers` threads, passing each
+    /// outcome to `emit` in the same order as `solutions`.
+    pub fn run_all(&self, solutions: &[Solution], workers: usize, emit: impl FnMut(Outcome)) {
+        run_ordered(solutions.to_vec(), workers, |s| self.run(s), emit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solutions() {
+        assert_eq!(Solution::new(1, 2).bin_name(), "day01-part2");
+        assert_eq!(Solution::new(25, 1).bin_name(), "day25-part1");
+
+        // Registered in day order, each just once...
+        assert!(SOLUTIONS.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_run_ordered() {
+        // Later jobs finish first, but come out in order...
+        let jobs = (0..8u64).collect::<Vec<_>>();
+        let mut out = Vec::new();
+        run_ordered(
+            jobs,
+            4,
+            |i| {
+                thread::sleep(Duration::from_millis(40 - i * 5));
+                i * 10
+            },
+            |r| out.push(r),
+        );
+        assert_eq!(out, vec![0, 10, 20, 30, 40, 50, 60, 70]);
+
+        let mut out = Vec::new();
+        run_ordered(Vec::<u8>::new(), 0, |i| i, |r| out.push(r));
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_last_line() {
+        assert_eq!(
+            last_line("a\nDone. Result: 12\n\n"),
+            Some("Done. Result: 12".to_string())
+        );
+        assert_eq!(last_line(""), None);
+    }
+
+    #[test]
+    fn test_find_answer() {
+        assert_eq!(
+            find_answer("There are 5 energized spaces\nAnswer: 5\nThere are 0 remaining beams\n"),
+            Some("5".to_string())
+        );
+        assert_eq!(find_answer("Answer: 1\nAnswer: 2\n"), Some("2".to_string()));
+        assert_eq!(find_answer("res: Some(650599855)\n"), None);
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let stderr = "thread 'main' panicked at src/bin/day08-part2.rs:12:5:\n\
+                      Invalid direction\n\
+                      note: run with `RUST_BACKTRACE=1` for a backtrace\n";
+        assert_eq!(panic_message(stderr), Some("Invalid direction".to_string()));
+        assert_eq!(panic_message("Error: oops"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_command() {
+        let sh =
+            |script: &str, timeout| run_command(Command::new("sh").args(["-c", script]), timeout);
+        assert_eq!(
+            sh("echo 'Answer: 42'; echo done", None),
+            (Status::Ok, Some("42".to_string()))
+        );
+        assert_eq!(
+            sh("echo bad >&2; exit 1", None),
+            (Status::Error("bad".to_string()), None)
+        );
+        assert_eq!(
+            sh("echo 'panicked at x:' >&2; echo boom >&2; exit 101", None),
+            (Status::Panic("boom".to_string()), None)
+        );
+
+        // Hangs are killed, keeping what was printed so far...
+        let start = Instant::now();
+        let (status, answer) = sh(
+            "echo 'Answer: partial'; exec sleep 10",
+            Some(Duration::from_millis(100)),
+        );
+        assert_eq!(status, Status::Timeout);
+        assert_eq!(answer, Some("partial".to_string()));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_missing_binary() {
+        let runner = Runner::new(Path::new("does-not-exist"));
+        let outcome = runner.run(Solution::new(1, 1));
+        assert_eq!(outcome.status.label(), "ERROR");
+        assert!(outcome.row().contains("isn't built"));
+    }
+}
@@ -0,0 +1,370 @@
+use crate::runner::Solution;
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The README comments that the table of contents goes
+/// between.
+pub const GEN_BEGIN_COMMENT: &str = "<!-- GENERATED BEGIN -->";
+pub const GEN_END_COMMENT: &str = "<!-- GENERATED END -->";
+
+/// The start of the runner's list of solutions, which new
+/// days get added to.
+const SOLUTIONS_BEGIN: &str = "pub const SOLUTIONS: &[Solution] = &[";
+
+/// The starting point for a new day's solution. `{DAY}` is
+/// replaced with the zero-padded day.
+const TEMPLATE: &str = r#"use anyhow::Result;
+use aoc23::{parse_input_lines_by_name, print_answer};
+
+fn parse_line(line: &str) -> Result<String> {
+    Ok(line.to_string())
+}
+
+fn solve(lines: &[String]) -> usize {
+    lines.len()
+}
+
+fn main() -> Result<()> {
+    let lines = parse_input_lines_by_name(file!(), parse_line)?;
+    let answer = solve(&lines);
+    print_answer(answer);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The puzzle's answer for the example.
+    const EXAMPLE_ANSWER: Option<usize> = None;
+
+    #[test]
+    fn test_example() -> Result<()> {
+        let expected = EXAMPLE_ANSWER.expect("Fill in the example and EXAMPLE_ANSWER first");
+        let example = include_str!("../../data/{DAY}-example.txt");
+        let lines = aoc23::parse_lines(&example.lines().collect::<Vec<_>>(), parse_line)?;
+        assert_eq!(solve(&lines), expected);
+        Ok(())
+    }
+}
+"#;
+
+/// The source for a new solution to `solution`.
+pub fn template(solution: Solution) -> String {
+    TEMPLATE.replace("{DAY}", &format!("{:02}", solution.day))
+}
+
+/// The path (relative to the repo) of the example input
+/// for the `d`th day, which the template's test reads.
+pub fn example_path(d: u8) -> PathBuf {
+    PathBuf::from(format!("data/{:02}-example.txt", d))
+}
+
+/// The path (relative to the repo) of the puzzle input for
+/// the `d`th day, which the README links to.
+pub fn input_path(d: u8) -> PathBuf {
+    PathBuf::from(format!("data/{:02}.txt", d))
+}
+
+/// The path (relative to the repo) of a solution's source.
+pub fn bin_path(solution: Solution) -> PathBuf {
+    PathBuf::from(format!("src/bin/{}.rs", solution.bin_name()))
+}
+
+/// Finds the solutions in `bin_dir` (skipping binaries that
+/// aren't solutions, like the runner), in order.
+pub fn find_solutions(bin_dir: &Path) -> Result<Vec<Solution>> {
+    let re = Regex::new(r"^day(\d+)-part(1|2)\.rs$")?;
+    let mut solutions = Vec::new();
+    for entry in fs::read_dir(bin_dir)? {
+        let name = entry?.file_name();
+        let Some(cap) = name.to_str().and_then(|n| re.captures(n)) else {
+            continue;
+        };
+        let day = cap[1].parse::<u8>().map_err(|_| anyhow!("invalid day"))?;
+        let part = cap[2].parse::<u8>().map_err(|_| anyhow!("invalid part"))?;
+        solutions.push(Solution::new(day, part));
+    }
+    solutions.sort();
+    Ok(solutions)
+}
+
+/// The README's table of contents for `solutions` (which
+/// should be in order).
+pub fn toc(solutions: &[Solution]) -> Vec<String> {
+    let mut lines = Vec::new();
+    for s in solutions {
+        // If this is part 1, add the top-level day...
+        if s.part == 1 {
+            lines.push(format!("- Day {}:", s.day));
+            lines.push(format!("  - [Input Data](/data/{:02}.txt)", s.day));
+        }
+        lines.push(format!(
+            "  - [Part {}](/src/bin/{}.rs)",
+            s.part,
+            s.bin_name()
+        ));
+    }
+    lines
+}
+
+/// Replaces whatever's between the generated comments in
+/// `readme` with `generated`, failing if they're missing.
+fn replace_generated_checked(readme: &str, generated: &[String]) -> Result<String> {
+    let lines = readme.lines().map(str::trim).collect::<Vec<_>>();
+    let begin = lines.iter().position(|&l| l == GEN_BEGIN_COMMENT);
+    let end = lines.iter().rposition(|&l| l == GEN_END_COMMENT);
+    match (begin, end) {
+        (Some(b), Some(e)) if b < e => Ok(replace_generated(readme, generated)),
+        _ => Err(anyhow!("Couldn't find the README's generated section")),
+    }
+}
+
+/// Replaces whatever's between the generated comments in
+/// `readme` with `generated`.
+pub fn replace_generated(readme: &str, generated: &[String]) -> String {
+    let mut out = Vec::new();
+    let mut skipping = false;
+    for line in readme.lines() {
+        match (line.trim(), skipping) {
+            (GEN_BEGIN_COMMENT, false) => {
+                out.push(line.to_string());
+                out.extend(generated.iter().cloned());
+                skipping = true;
+            }
+            (GEN_END_COMMENT, true) => {
+                out.push(line.to_string());
+                skipping = false;
+            }
+            (_, true) => {}
+            _ => out.push(line.to_string()),
+        }
+    }
+    let mut out = out.join("\n");
+    if readme.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Adds `solution` to the runner's list of solutions in
+/// `source` (the text of `runner.rs`), keeping it in order.
+pub fn register(source: &str, solution: Solution) -> Result<String> {
+    let re = Regex::new(r"^\s*Solution::new\((\d+), (\d+)\),$")?;
+    let begin = source
+        .lines()
+        .position(|l| l == SOLUTIONS_BEGIN)
+        .ok_or(anyhow!("Couldn't find the list of solutions"))?;
+
+    // Find the entry to put it before (or the end of the
+    // list)...
+    let mut lines = source.lines().collect::<Vec<_>>();
+    let mut at = None;
+    for (i, line) in lines.iter().enumerate().skip(begin + 1) {
+        let Some(cap) = re.captures(line) else {
+            at = Some(i);
+            break;
+        };
+        let existing = Solution::new(cap[1].parse()?, cap[2].parse()?);
+        if existing == solution {
+            return Err(anyhow!("{} is already registered", solution.bin_name()));
+        }
+        if existing > solution {
+            at = Some(i);
+            break;
+        }
+    }
+    let at = at.ok_or(anyhow!("Couldn't find the end of the list of solutions"))?;
+
+    let entry = format!("    Solution::new({}, {}),", solution.day, solution.part);
+    lines.insert(at, &entry);
+    let mut out = lines.join("\n");
+    if source.ends_with('\n') {
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Regenerates the table of contents in the README in
+/// `root` (the top of the repo).
+pub fn update_readme(root: &Path) -> Result<()> {
+    let solutions = find_solutions(&root.join("src/bin"))?;
+    let path = root.join("README.md");
+    let readme = fs::read_to_string(&path)?;
+    fs::write(&path, replace_generated_checked(&readme, &toc(&solutions))?)?;
+    Ok(())
+}
+
+/// Sets up part 1 of the `d`th day in `root` (the top of
+/// the repo): the solution from the template, an empty
+/// example input, an empty input (unless it's already
+/// there), the runner entry and the README's table of
+/// contents. Nothing is overwritten; if the solution or
+/// example are already there, or anything else is wrong,
+/// nothing is changed. Returns the files it created.
+pub fn new_day(root: &Path, d: u8) -> Result<Vec<PathBuf>> {
+    if !(1..=25).contains(&d) {
+        return Err(anyhow!("There's no day {} (expected 1 to 25)", d));
+    }
+    let solution = Solution::new(d, 1);
+
+    // Work out all the new files before writing anything...
+    let mut files = vec![
+        (bin_path(solution), template(solution)),
+        (example_path(d), String::new()),
+    ];
+    if let Some((p, _)) = files.iter().find(|(p, _)| root.join(p).exists()) {
+        return Err(anyhow!("{} already exists", p.display()));
+    }
+    if !root.join(input_path(d)).exists() {
+        files.push((input_path(d), String::new()));
+    }
+    let created = files.iter().map(|(p, _)| p.clone()).collect::<Vec<_>>();
+
+    let runner_path = PathBuf::from("src/runner.rs");
+    let runner = register(&fs::read_to_string(root.join(&runner_path))?, solution)?;
+    files.push((runner_path, runner));
+
+    let mut solutions = find_solutions(&root.join("src/bin"))?;
+    solutions.push(solution);
+    solutions.sort();
+    let readme_path = PathBuf::from("README.md");
+    let readme = fs::read_to_string(root.join(&readme_path))?;
+    files.push((
+        readme_path,
+        replace_generated_checked(&readme, &toc(&solutions))?,
+    ));
+
+    for (path, contents) in files {
+        fs::write(root.join(path), contents)?;
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUNNER: &str = "\
+pub const SOLUTIONS: &[Solution] = &[
+    Solution::new(1, 1),
+    Solution::new(1, 2),
+    Solution::new(25, 1),
+];
+";
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let out = register(RUNNER, Solution::new(3, 1))?;
+        assert!(out.contains("(1, 2),\n    Solution::new(3, 1),\n    Solution::new(25, 1)"));
+        let out = register(&out, Solution::new(3, 2))?;
+        assert!(out.contains("(3, 1),\n    Solution::new(3, 2),\n"));
+        assert!(register(&out, Solution::new(3, 2)).is_err());
+
+        // At the end...
+        let out = register(RUNNER, Solution::new(25, 2))?;
+        assert!(out.ends_with("(25, 1),\n    Solution::new(25, 2),\n];\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_toc() {
+        let readme = format!(
+            "# AoC\n{}\n- old\n{}\n\nMore\n",
+            GEN_BEGIN_COMMENT, GEN_END_COMMENT
+        );
+        let solutions = [
+            Solution::new(1, 1),
+            Solution::new(1, 2),
+            Solution::new(3, 1),
+        ];
+        assert_eq!(
+            replace_generated(&readme, &toc(&solutions)),
+            format!(
+                "# AoC\n{}\n{}\n{}\n\nMore\n",
+                GEN_BEGIN_COMMENT,
+                [
+                    "- Day 1:",
+                    "  - [Input Data](/data/01.txt)",
+                    "  - [Part 1](/src/bin/day01-part1.rs)",
+                    "  - [Part 2](/src/bin/day01-part2.rs)",
+                    "- Day 3:",
+                    "  - [Input Data](/data/03.txt)",
+                    "  - [Part 1](/src/bin/day03-part1.rs)",
+                ]
+                .join("\n"),
+                GEN_END_COMMENT
+            )
+        );
+    }
+
+    #[test]
+    fn test_new_day() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("aoc23-new-day-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/bin"))?;
+        fs::create_dir_all(root.join("data"))?;
+        fs::write(root.join("src/runner.rs"), RUNNER)?;
+        fs::write(root.join("src/bin/day01-part1.rs"), "")?;
+        fs::write(root.join("src/bin/aoc.rs"), "")?;
+        let readme = format!("{}\n{}\n", GEN_BEGIN_COMMENT, GEN_END_COMMENT);
+        fs::write(root.join("README.md"), readme)?;
+
+        let created = new_day(&root, 7)?;
+        assert_eq!(
+            created,
+            vec![
+                bin_path(Solution::new(7, 1)),
+                example_path(7),
+                input_path(7)
+            ]
+        );
+        let source = fs::read_to_string(root.join("src/bin/day07-part1.rs"))?;
+        assert!(source.contains("include_str!(\"../../data/07-example.txt\")"));
+        assert!(source.contains("const EXAMPLE_ANSWER: Option<usize> = None;"));
+        assert!(!source.contains("#[ignore"));
+        assert!(fs::read_to_string(root.join("src/runner.rs"))?.contains("new(7, 1)"));
+        assert!(fs::read_to_string(root.join("README.md"))?.contains("- Day 7:"));
+
+        // Doing it again (or for a day that's started)
+        // doesn't touch anything...
+        fs::write(root.join("src/bin/day07-part1.rs"), "mine")?;
+        assert!(new_day(&root, 7).is_err());
+        assert!(new_day(&root, 1).is_err());
+        assert!(new_day(&root, 26).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("src/bin/day07-part1.rs"))?,
+            "mine"
+        );
+        assert!(!root.join("data/01-example.txt").exists());
+
+        // An input that's already there is kept...
+        fs::write(root.join("data/08.txt"), "input")?;
+        assert_eq!(new_day(&root, 8)?.len(), 2);
+        assert_eq!(fs::read_to_string(root.join("data/08.txt"))?, "input");
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_day_without_generated_section() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("aoc23-new-day-bad-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/bin"))?;
+        fs::create_dir_all(root.join("data"))?;
+        fs::write(root.join("src/runner.rs"), RUNNER)?;
+        fs::write(root.join("README.md"), "# AoC\n")?;
+
+        // The README's checked before anything's written...
+        assert!(new_day(&root, 7).is_err());
+        assert!(!root.join("src/bin/day07-part1.rs").exists());
+        assert!(!root.join("data/07-example.txt").exists());
+        assert!(!root.join("data/07.txt").exists());
+        assert_eq!(fs::read_to_string(root.join("src/runner.rs"))?, RUNNER);
+        assert!(update_readme(&root).is_err());
+
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
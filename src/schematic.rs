@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Result};
+
+/// A number in the schematic, spanning columns
+/// `start..=end` of `row`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub id: usize,
+    pub value: usize,
+    pub row: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// A symbol (anything that isn't a digit or `.`) in
+/// the schematic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub id: usize,
+    pub ch: char,
+    pub row: usize,
+    pub col: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    Number(usize),
+    Symbol(usize),
+}
+
+/// An engine schematic (day 3), with the numbers and
+/// symbols extracted once and an index from each cell to
+/// the number or symbol covering it, so adjacency queries
+/// only look at neighboring cells.
+#[derive(Debug, Clone)]
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    cells: Vec<Vec<Cell>>,
+}
+
+impl Schematic {
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Self> {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+        let mut cells = Vec::with_capacity(lines.len());
+        for (row, line) in lines.iter().enumerate() {
+            let chars = line.as_ref().chars().collect::<Vec<_>>();
+            let mut row_cells = vec![Cell::Empty; chars.len()];
+            let mut col = 0;
+            while col < chars.len() {
+                let c = chars[col];
+                if c.is_ascii_digit() {
+                    // Read the whole number...
+                    let start = col;
+                    while col < chars.len() && chars[col].is_ascii_digit() {
+                        row_cells[col] = Cell::Number(numbers.len());
+                        col += 1;
+                    }
+                    let text = chars[start..col].iter().collect::<String>();
+                    let value = text.parse::<usize>().map_err(|e| {
+                        anyhow!("Failed to parse \"{}\" on line {}: {}", text, row + 1, e)
+                    })?;
+                    numbers.push(Number {
+                        id: numbers.len(),
+                        value,
+                        row,
+                        start,
+                        end: col - 1,
+                    });
+                    continue;
+                }
+                if c != '.' {
+                    row_cells[col] = Cell::Symbol(symbols.len());
+                    symbols.push(Symbol {
+                        id: symbols.len(),
+                        ch: c,
+                        row,
+                        col,
+                    });
+                }
+                col += 1;
+            }
+            cells.push(row_cells);
+        }
+        Ok(Self {
+            numbers,
+            symbols,
+            cells,
+        })
+    }
+
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Cell {
+        self.cells
+            .get(row)
+            .and_then(|r| r.get(col))
+            .copied()
+            .unwrap_or(Cell::Empty)
+    }
+
+    /// Returns the cells in the box one cell larger than
+    /// `row`, `start..=end` on every side.
+    fn surrounding(&self, row: usize, start: usize, end: usize) -> impl Iterator<Item = Cell> + '_ {
+        let rows = row.saturating_sub(1)..=row + 1;
+        rows.flat_map(move |r| {
+            (start.saturating_sub(1)..=end + 1).map(move |c| self.cell(r, c))
+        })
+    }
+
+    /// Returns the numbers touching the symbol `symbol_id`
+    /// (including diagonally), in id order.
+    pub fn numbers_adjacent_to(&self, symbol_id: usize) -> Vec<&Number> {
+        let s = &self.symbols[symbol_id];
+        let mut ids = self
+            .surrounding(s.row, s.col, s.col)
+            .filter_map(|c| match c {
+                Cell::Number(id) => Some(id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.into_iter().map(|id| &self.numbers[id]).collect()
+    }
+
+    /// Returns the symbols touching the number `number_id`
+    /// (including diagonally), in id order.
+    pub fn symbols_adjacent_to(&self, number_id: usize) -> Vec<&Symbol> {
+        let n = &self.numbers[number_id];
+        let mut ids = self
+            .surrounding(n.row, n.start, n.end)
+            .filter_map(|c| match c {
+                Cell::Symbol(id) => Some(id),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.into_iter().map(|id| &self.symbols[id]).collect()
+    }
+
+    /// Returns the numbers touching at least one symbol.
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .filter(|n| !self.symbols_adjacent_to(n.id).is_empty())
+    }
+
+    /// Returns every symbol with exactly `k` adjacent
+    /// numbers, along with those numbers.
+    pub fn symbols_with_adjacent(&self, k: usize) -> impl Iterator<Item = (&Symbol, Vec<&Number>)> {
+        self.symbols.iter().filter_map(move |s| {
+            let adj = self.numbers_adjacent_to(s.id);
+            if adj.len() == k {
+                Some((s, adj))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the gears: `*` symbols with exactly two
+    /// adjacent numbers, along with those numbers.
+    pub fn gears(&self) -> impl Iterator<Item = (&Symbol, Vec<&Number>)> {
+        self.symbols_with_adjacent(2).filter(|(s, _)| s.ch == '*')
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Schematic {
+        let lines = [
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ];
+        Schematic::parse(&lines).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let s = example();
+        assert_eq!(s.numbers().len(), 10);
+        assert_eq!(s.symbols().len(), 6);
+        assert_eq!(
+            s.numbers()[1],
+            Number {
+                id: 1,
+                value: 114,
+                row: 0,
+                start: 5,
+                end: 7
+            }
+        );
+        assert_eq!(s.symbols()[0].ch, '*');
+        assert_eq!((s.symbols()[0].row, s.symbols()[0].col), (1, 3));
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let s = example();
+        let values = |ns: Vec<&Number>| ns.iter().map(|n| n.value).collect::<Vec<_>>();
+        assert_eq!(values(s.numbers_adjacent_to(0)), vec![467, 35]);
+        assert_eq!(values(s.numbers_adjacent_to(2)), vec![617]);
+        assert!(s.symbols_adjacent_to(1).is_empty());
+        assert_eq!(s.symbols_adjacent_to(0)[0].ch, '*');
+        assert_eq!(s.part_numbers().map(|n| n.value).sum::<usize>(), 4361);
+    }
+
+    #[test]
+    fn test_gears() {
+        let s = example();
+        let ratios = s
+            .gears()
+            .map(|(_, ns)| ns.iter().map(|n| n.value).product::<usize>())
+            .collect::<Vec<_>>();
+        assert_eq!(ratios, vec![16345, 451490]);
+        assert_eq!(s.symbols_with_adjacent(1).count(), 4);
+    }
+
+    #[test]
+    fn test_number_at_line_end() {
+        let s = Schematic::parse(&["..12", "...#"]).unwrap();
+        assert_eq!(s.part_numbers().map(|n| n.value).collect::<Vec<_>>(), vec![12]);
+    }
+}
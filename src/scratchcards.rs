@@ -0,0 +1,359 @@
+use crate::error::ParseError;
+use crate::parse::{self, Parser};
+use crate::parse_lines;
+use anyhow::{anyhow, Result};
+use std::fmt;
+
+/// A set of card numbers.
+///
+/// Numbers below 128 (which is all of them, in practice)
+/// live in a bitset so intersections are a single `&` and
+/// popcount. Anything larger falls back to a sorted list.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberSet {
+    small: u128,
+    large: Vec<usize>,
+}
+
+impl NumberSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, n: usize) {
+        if n < 128 {
+            self.small |= 1 << n;
+        } else if let Err(i) = self.large.binary_search(&n) {
+            self.large.insert(i, n);
+        }
+    }
+
+    pub fn contains(&self, n: usize) -> bool {
+        if n < 128 {
+            self.small & (1 << n) != 0
+        } else {
+            self.large.binary_search(&n).is_ok()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.small.count_ones() as usize + self.large.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Counts the numbers in both sets.
+    pub fn intersection_len(&self, other: &Self) -> usize {
+        let small = (self.small & other.small).count_ones() as usize;
+        let large = self.large.iter().filter(|n| other.contains(**n)).count();
+        small + large
+    }
+
+    /// Parses whitespace-separated numbers.
+    pub fn parse(s: &str) -> Result<Self> {
+        Ok(parse::line(s, Self::parse_from)?)
+    }
+
+    /// Parses space-separated numbers from the middle of a
+    /// line, stopping at the first thing that isn't one.
+    pub fn parse_from(p: &mut Parser) -> Result<Self, ParseError> {
+        let mut set = Self::new();
+        for n in p.many(|p| {
+            p.ws();
+            p.uint()
+        })? {
+            set.insert(n);
+        }
+        Ok(set)
+    }
+}
+
+/// A single scratchcard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: usize,
+    pub winning: NumberSet,
+    pub mine: NumberSet,
+}
+
+impl Card {
+    /// Parses a line like `Card 1: 41 48 83 | 83 86 17`.
+    pub fn parse(line: &str) -> Result<Self> {
+        let card = parse::line(line, |p| {
+            p.literal("Card")?;
+            p.ws();
+            let id = p.uint()?;
+            p.literal(":")?;
+            let winning = NumberSet::parse_from(p)?;
+            p.ws();
+            p.literal("|")?;
+            let mine = NumberSet::parse_from(p)?;
+            Ok(Self { id, winning, mine })
+        })?;
+        Ok(card)
+    }
+
+    /// The number of winning numbers I have.
+    pub fn matches(&self) -> usize {
+        self.winning.intersection_len(&self.mine)
+    }
+
+    /// The card's point value (part 1): 1 for the first
+    /// match, doubled for each match after that. Fails if
+    /// that's too big for a `usize`.
+    pub fn score(&self) -> Result<usize> {
+        match self.matches() {
+            0 => Ok(0),
+            n => 1usize.checked_shl((n - 1) as u32).ok_or(anyhow!(
+                "Card {}'s score is too big ({} matches)",
+                self.id,
+                n
+            )),
+        }
+    }
+}
+
+/// What happened to one card while playing out the
+/// copies (part 2).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardTrace {
+    pub id: usize,
+    pub matches: usize,
+
+    /// How many copies of this card we end up with.
+    pub copies: usize,
+
+    /// How many copies of later cards this card wins
+    /// (`copies` times the number of cards it wins).
+    pub produced: usize,
+}
+
+impl fmt::Display for CardTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Card {}: {} matches, {} copies, produces {} copies",
+            self.id, self.matches, self.copies, self.produced
+        )
+    }
+}
+
+/// A pile of scratchcards (day 4).
+#[derive(Debug, Clone, Default)]
+pub struct Scratchcards {
+    cards: Vec<Card>,
+}
+
+impl Scratchcards {
+    pub fn parse<S: AsRef<str>>(lines: &[S]) -> Result<Self> {
+        let cards = parse_lines(lines, Card::parse)?;
+        Ok(Self { cards })
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// The total points for all the cards (part 1).
+    pub fn points(&self) -> Result<usize> {
+        self.cards.iter().try_fold(0usize, |total, c| {
+            total
+                .checked_add(c.score()?)
+                .ok_or(anyhow!("The total points are too big"))
+        })
+    }
+
+    /// Returns how many copies of each card we end up with,
+    /// failing if that's too many for a `usize`.
+    ///
+    /// A card with `m` matches adds its copy count to each of
+    /// the next `m` cards. Rather than adding to each of them,
+    /// we add at the start of the range and subtract just past
+    /// the end, keeping a running sum, so this is linear in
+    /// the number of cards. Wins past the last card are dropped.
+    pub fn copies(&self) -> Result<Vec<usize>> {
+        let n = self.cards.len();
+        let mut starts = vec![0usize; n + 1];
+        let mut ends = vec![0usize; n + 1];
+        let mut copies = Vec::with_capacity(n);
+        let mut extra = 0usize;
+        for (i, card) in self.cards.iter().enumerate() {
+            let too_many = || anyhow!("Too many copies of card {}", card.id);
+
+            // (Everything ending here was added before, so
+            // taking it off first can't underflow)...
+            extra = (extra - ends[i])
+                .checked_add(starts[i])
+                .ok_or_else(too_many)?;
+            let count = extra.checked_add(1).ok_or_else(too_many)?;
+            copies.push(count);
+
+            let end = (i + 1 + card.matches()).min(n);
+            if i + 1 < end {
+                starts[i + 1] = starts[i + 1].checked_add(count).ok_or_else(too_many)?;
+                ends[end] = ends[end].checked_add(count).ok_or_else(too_many)?;
+            }
+        }
+        Ok(copies)
+    }
+
+    /// The total number of cards we end up with (part 2).
+    pub fn total_cards(&self) -> Result<usize> {
+        self.copies()?.into_iter().try_fold(0usize, |total, c| {
+            total
+                .checked_add(c)
+                .ok_or(anyhow!("The total number of cards is too big"))
+        })
+    }
+
+    /// Returns a line for each card, showing how many copies
+    /// it ends up with and how many copies it produces.
+    pub fn trace(&self) -> Result<Vec<CardTrace>> {
+        let n = self.cards.len();
+        self.cards
+            .iter()
+            .zip(self.copies()?)
+            .enumerate()
+            .map(|(i, (card, copies))| {
+                let won = card.matches().min(n - i - 1);
+                let produced = copies
+                    .checked_mul(won)
+                    .ok_or(anyhow!("Card {} produces too many copies", card.id))?;
+                Ok(CardTrace {
+                    id: card.id,
+                    matches: card.matches(),
+                    copies,
+                    produced,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Scratchcards {
+        Scratchcards::parse(&[
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_number_set() -> Result<()> {
+        let a = NumberSet::parse("1 5 99 127 128 500")?;
+        let b = NumberSet::parse("5 6 127 500 501")?;
+        assert_eq!(a.len(), 6);
+        assert!(a.contains(99) && a.contains(500));
+        assert!(!a.contains(6) && !a.contains(501));
+        assert_eq!(a.intersection_len(&b), 3);
+        assert!(NumberSet::parse("1 x").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_card() -> Result<()> {
+        let cards = example();
+        let matches = cards
+            .cards()
+            .iter()
+            .map(|c| c.matches())
+            .collect::<Vec<_>>();
+        assert_eq!(matches, vec![4, 2, 2, 1, 0, 0]);
+        assert_eq!(cards.cards()[0].score()?, 8);
+        assert!(Card::parse("Card x: 1 | 2").is_err());
+        assert!(Card::parse("Card 1: 1 2").is_err());
+
+        // Errors point at the problem...
+        let e = Scratchcards::parse(&["Card 1: 1 | 2", "Card 2: 1 2 | 3 x"]).unwrap_err();
+        let e = e.downcast::<ParseError>().unwrap();
+        assert_eq!((e.line, e.column), (Some(2), 17));
+        Ok(())
+    }
+
+    #[test]
+    fn test_score_overflow() -> Result<()> {
+        // 70 matches, most of them too big for the bitset...
+        let nums = (100..170)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let card = Card::parse(&format!("Card 7: {} | {}", nums, nums))?;
+        assert_eq!(card.matches(), 70);
+        assert!(card.score().is_err());
+
+        // ...but 64 still fit...
+        let mine = (100..164)
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let card = Card::parse(&format!("Card 7: {} | {}", nums, mine))?;
+        assert_eq!(card.score()?, 1 << 63);
+        Ok(())
+    }
+
+    #[test]
+    fn test_points_and_copies() -> Result<()> {
+        let cards = example();
+        assert_eq!(cards.points()?, 13);
+        assert_eq!(cards.copies()?, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(cards.total_cards()?, 30);
+        Ok(())
+    }
+
+    #[test]
+    fn test_copies_overflow() -> Result<()> {
+        // A card with `m` matches (and a number that's never
+        // a match, so `m` can be 0)...
+        let card = |id: usize, m: usize| {
+            let nums = (1..=m).map(|n| format!(" {}", n)).collect::<String>();
+            format!("Card {}:{} 99 |{} 98", id, nums, nums)
+        };
+
+        // Each of the first 63 cards wins every card up to
+        // the 63rd, so the copies double each time (up to
+        // 2^62), then the 63rd wins the next four...
+        let mut lines = (0..62).map(|i| card(i + 1, 62 - i)).collect::<Vec<_>>();
+        lines.push(card(63, 4));
+        lines.extend((64..=67).map(|id| card(id, 0)));
+        let cards = Scratchcards::parse(&lines)?;
+        let copies = cards.copies()?;
+        assert_eq!((copies[62], copies[63]), (1 << 62, (1 << 62) + 1));
+        assert!(cards.trace().is_err());
+        assert!(cards.total_cards().is_err());
+
+        // ...and with one more doubling, the copies overflow.
+        let lines = (0..65).map(|i| card(i + 1, 64 - i)).collect::<Vec<_>>();
+        assert!(Scratchcards::parse(&lines)?.copies().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_trace() -> Result<()> {
+        let trace = example().trace()?;
+        assert_eq!(
+            trace[1].to_string(),
+            "Card 2: 2 matches, 2 copies, produces 4 copies"
+        );
+        let produced = trace.iter().map(|t| t.produced).collect::<Vec<_>>();
+        assert_eq!(produced, vec![4, 4, 8, 8, 0, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_wins_past_the_end() -> Result<()> {
+        let cards = Scratchcards::parse(&["Card 1: 1 2 3 | 1 2 3", "Card 2: 4 | 4"])?;
+        assert_eq!(cards.copies()?, vec![1, 2]);
+        assert_eq!(cards.trace()?[1].produced, 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,374 @@
+use crate::{has_flag, out_path};
+use anyhow::Result;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// The command line flag that plays a solution's
+/// simulation in the terminal.
+pub const ANIMATE_FLAG: &str = "--animate";
+
+/// The command line flag that writes every frame of a
+/// solution's simulation to `out/<name>-frames.txt`.
+pub const FRAMES_FLAG: &str = "--frames";
+
+/// How long each frame is shown for while playing.
+pub const FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// A puzzle that evolves one step at a time (eg rocks
+/// rolling or beams spreading).
+pub trait Simulation {
+    /// Advances the simulation by one step. Returns `false`
+    /// (without changing anything) once it's finished.
+    fn step(&mut self) -> bool;
+
+    /// Draws the current state as text.
+    fn render(&self) -> String;
+}
+
+/// A command for the terminal player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Next,
+    Back,
+    TogglePlay,
+    Jump(usize),
+    End,
+    Help,
+    Quit,
+}
+
+impl Command {
+    /// Parses a line typed into the player. An empty line
+    /// steps forward.
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut parts = s.split_whitespace();
+        let cmd = match parts.next() {
+            None | Some("n") => Self::Next,
+            Some("b") => Self::Back,
+            Some("p") => Self::TogglePlay,
+            Some("g") => Self::Jump(parts.next()?.parse().ok()?),
+            Some("e") => Self::End,
+            Some("h") | Some("?") => Self::Help,
+            Some("q") => Self::Quit,
+            _ => return None,
+        };
+        match parts.next() {
+            None => Some(cmd),
+            Some(_) => None,
+        }
+    }
+}
+
+const HELP: &str = "\
+<enter>/n  next frame
+b          previous frame
+p          play/pause
+g <N>      jump to frame N
+e          jump to the last frame
+q          quit (the simulation still runs to the end)";
+
+/// Steps through a `Simulation`, keeping every rendered
+/// frame so it can be replayed, rewound or jumped around.
+pub struct Player<S: Simulation> {
+    sim: S,
+    frames: Vec<String>,
+    current: usize,
+    finished: bool,
+    playing: bool,
+}
+
+impl<S: Simulation> Player<S> {
+    pub fn new(sim: S) -> Self {
+        let frames = vec![sim.render()];
+        Self {
+            sim,
+            frames,
+            current: 0,
+            finished: false,
+            playing: false,
+        }
+    }
+
+    /// Steps the simulation until frame `n` exists or it's
+    /// finished. Returns whether frame `n` exists.
+    fn record_until(&mut self, n: usize) -> bool {
+        while self.frames.len() <= n && !self.finished {
+            if self.sim.step() {
+                self.frames.push(self.sim.render());
+            } else {
+                self.finished = true;
+            }
+        }
+        n < self.frames.len()
+    }
+
+    /// Returns frame `n`, running the simulation forward
+    /// if it hasn't been reached yet.
+    pub fn frame(&mut self, n: usize) -> Option<&str> {
+        if self.record_until(n) {
+            Some(&self.frames[n])
+        } else {
+            None
+        }
+    }
+
+    /// The index of the frame currently being shown.
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// Runs the simulation to the end and returns every frame.
+    pub fn all_frames(&mut self) -> &[String] {
+        self.record_until(usize::MAX);
+        &self.frames
+    }
+
+    /// Writes every frame, each under a `== frame N ==`
+    /// header, so runs can be diffed later.
+    pub fn write_log<W: Write>(&mut self, mut w: W) -> io::Result<()> {
+        for (i, frame) in self.all_frames().iter().enumerate() {
+            writeln!(w, "== frame {} ==", i)?;
+            write!(w, "{}", frame)?;
+            if !frame.ends_with('\n') {
+                writeln!(w)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Applies a command. Returns `false` if the player
+    /// should stop.
+    pub fn apply(&mut self, cmd: Command) -> bool {
+        match cmd {
+            Command::Next => {
+                if self.record_until(self.current + 1) {
+                    self.current += 1;
+                } else {
+                    self.playing = false;
+                }
+            }
+            Command::Back => {
+                self.current = self.current.saturating_sub(1);
+            }
+            Command::TogglePlay => {
+                self.playing = !self.playing;
+            }
+            Command::Jump(n) => {
+                self.record_until(n);
+                self.current = n.min(self.frames.len() - 1);
+            }
+            Command::End => {
+                self.record_until(usize::MAX);
+                self.current = self.frames.len() - 1;
+            }
+            Command::Help => {}
+            Command::Quit => return false,
+        }
+        true
+    }
+
+    /// Draws the current frame and a status line.
+    fn draw<W: Write>(&self, w: &mut W, message: &str) -> io::Result<()> {
+        let total = if self.finished {
+            format!("{}", self.frames.len() - 1)
+        } else {
+            "?".to_string()
+        };
+        write!(w, "\x1b[2J\x1b[H")?;
+        write!(w, "{}", self.frames[self.current])?;
+        writeln!(
+            w,
+            "\nframe {}/{}{}  (h for help)",
+            self.current,
+            total,
+            if self.playing { " [playing]" } else { "" },
+        )?;
+        if !message.is_empty() {
+            writeln!(w, "{}", message)?;
+        }
+        w.flush()
+    }
+
+    /// Plays the simulation interactively, reading commands
+    /// from `commands` and drawing to `out`. While playing,
+    /// any command pauses playback first.
+    pub fn play<W: Write>(&mut self, commands: Receiver<String>, mut out: W) -> io::Result<()> {
+        let mut message = String::new();
+        loop {
+            self.draw(&mut out, &message)?;
+            message.clear();
+
+            // Wait for a command, or for the next frame if
+            // we're playing...
+            let line = if self.playing {
+                match commands.recv_timeout(FRAME_DELAY) {
+                    Ok(line) => {
+                        self.playing = false;
+                        line
+                    }
+                    Err(RecvTimeoutError::Timeout) => {
+                        self.apply(Command::Next);
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => return Ok(()),
+                }
+            } else {
+                match commands.recv() {
+                    Ok(line) => line,
+                    Err(_) => return Ok(()),
+                }
+            };
+
+            match Command::parse(&line) {
+                Some(Command::Help) => message = HELP.to_string(),
+                Some(cmd) => {
+                    if !self.apply(cmd) {
+                        return Ok(());
+                    }
+                }
+                None => message = format!("Unknown command {:?} (h for help)", line.trim()),
+            }
+        }
+    }
+
+    /// Runs the simulation to the end and returns it.
+    pub fn finish(mut self) -> S {
+        while self.sim.step() {}
+        self.sim
+    }
+}
+
+/// Reads lines from stdin on a background thread so the
+/// player can keep animating while waiting for input.
+fn stdin_lines() -> Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+/// Runs `sim` to the end and returns it.
+///
+/// With `--frames`, every frame is written to
+/// `out/<name>-frames.txt` (where `<name>` is the file
+/// stem of `name`, eg `file!()`). With `--animate`, the
+/// simulation is played in the terminal first.
+pub fn run<S: Simulation>(sim: S, name: &str) -> Result<S> {
+    let animate = has_flag(ANIMATE_FLAG);
+    let frames = has_flag(FRAMES_FLAG);
+    if !animate && !frames {
+        let mut sim = sim;
+        while sim.step() {}
+        return Ok(sim);
+    }
+
+    let mut player = Player::new(sim);
+    if animate {
+        player.play(stdin_lines(), io::stdout())?;
+    }
+    if frames {
+        let path = out_path(name, "-frames.txt")?;
+        player.write_log(io::BufWriter::new(std::fs::File::create(&path)?))?;
+        println!("Wrote {}", path.display());
+    }
+    Ok(player.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts up to a limit, one per step.
+    struct Counter {
+        n: usize,
+        limit: usize,
+    }
+
+    impl Simulation for Counter {
+        fn step(&mut self) -> bool {
+            if self.n == self.limit {
+                return false;
+            }
+            self.n += 1;
+            true
+        }
+
+        fn render(&self) -> String {
+            format!("{}\n", self.n)
+        }
+    }
+
+    fn counter(limit: usize) -> Player<Counter> {
+        Player::new(Counter { n: 0, limit })
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(Command::parse(""), Some(Command::Next));
+        assert_eq!(Command::parse("n"), Some(Command::Next));
+        assert_eq!(Command::parse(" g 12 "), Some(Command::Jump(12)));
+        assert_eq!(Command::parse("g"), None);
+        assert_eq!(Command::parse("g x"), None);
+        assert_eq!(Command::parse("p now"), None);
+        assert_eq!(Command::parse("z"), None);
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut player = counter(3);
+        assert_eq!(player.frame(2), Some("2\n"));
+        assert_eq!(player.frame(4), None);
+        assert_eq!(player.all_frames().len(), 4);
+        assert_eq!(player.finish().n, 3);
+    }
+
+    #[test]
+    fn test_apply() {
+        let mut player = counter(3);
+        player.apply(Command::Next);
+        player.apply(Command::Next);
+        assert_eq!(player.current(), 2);
+        player.apply(Command::Back);
+        assert_eq!(player.current(), 1);
+        player.apply(Command::Jump(10));
+        assert_eq!(player.current(), 3);
+        player.apply(Command::Jump(0));
+        player.apply(Command::End);
+        assert_eq!(player.current(), 3);
+        player.apply(Command::Next);
+        assert_eq!(player.current(), 3);
+        assert!(!player.apply(Command::Quit));
+    }
+
+    #[test]
+    fn test_write_log() -> Result<()> {
+        let mut out = Vec::new();
+        counter(2).write_log(&mut out)?;
+        assert_eq!(
+            String::from_utf8(out)?,
+            "== frame 0 ==\n0\n== frame 1 ==\n1\n== frame 2 ==\n2\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_play() -> Result<()> {
+        let (tx, rx) = mpsc::channel();
+        for line in ["", "g 2", "b", "q"] {
+            tx.send(line.to_string())?;
+        }
+        let mut player = counter(5);
+        let mut out = Vec::new();
+        player.play(rx, &mut out)?;
+        assert_eq!(player.current(), 1);
+        assert!(String::from_utf8(out)?.contains("frame 2/?"));
+        Ok(())
+    }
+}
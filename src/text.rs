@@ -0,0 +1,228 @@
+use std::collections::VecDeque;
+
+/// A single pattern match in some text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match {
+    /// Index of the pattern that matched.
+    pub pattern: usize,
+
+    /// Byte offset where the match starts.
+    pub start: usize,
+
+    /// Byte offset just past the end of the match.
+    pub end: usize,
+}
+
+const ROOT: usize = 0;
+
+/// Finds every (possibly overlapping) occurrence of a set
+/// of patterns in one pass over the text, using an
+/// Aho–Corasick automaton.
+///
+/// The automaton works on bytes, so patterns and text can
+/// be any UTF-8, but matches are reported as byte offsets.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    patterns: Vec<String>,
+
+    /// `next[state][byte]` is the state to move to after
+    /// reading `byte` (with failure links already folded in).
+    next: Vec<[u32; 256]>,
+
+    /// The patterns that end at each state, including the
+    /// ones reachable through failure links.
+    outputs: Vec<Vec<usize>>,
+}
+
+impl Matcher {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        let patterns = patterns
+            .iter()
+            .map(|p| p.as_ref().to_string())
+            .collect::<Vec<_>>();
+
+        // Build the trie. A transition of 0 means "none yet"
+        // (nothing points back at the root in a trie)...
+        let mut next: Vec<[u32; 256]> = vec![[0; 256]];
+        let mut outputs: Vec<Vec<usize>> = vec![Vec::new()];
+        for (i, p) in patterns.iter().enumerate() {
+            if p.is_empty() {
+                continue;
+            }
+            let mut state = ROOT;
+            for &b in p.as_bytes() {
+                if next[state][b as usize] == 0 {
+                    next.push([0; 256]);
+                    outputs.push(Vec::new());
+                    next[state][b as usize] = (next.len() - 1) as u32;
+                }
+                state = next[state][b as usize] as usize;
+            }
+            outputs[state].push(i);
+        }
+
+        // Walk the trie breadth-first, filling in the missing
+        // transitions from each state's failure state (which
+        // is always shallower, so already complete)...
+        let mut fail = vec![ROOT; next.len()];
+        let mut queue = next[ROOT]
+            .iter()
+            .filter(|&&s| s != 0)
+            .map(|&s| s as usize)
+            .collect::<VecDeque<_>>();
+        while let Some(state) = queue.pop_front() {
+            let inherited = outputs[fail[state]].clone();
+            outputs[state].extend(inherited);
+            let fallback = next[fail[state]];
+            for (b, slot) in next[state].iter_mut().enumerate() {
+                if *slot != 0 {
+                    fail[*slot as usize] = fallback[b] as usize;
+                    queue.push_back(*slot as usize);
+                } else {
+                    *slot = fallback[b];
+                }
+            }
+        }
+
+        Self {
+            patterns,
+            next,
+            outputs,
+        }
+    }
+
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Iterates over every match in `text`, in order of where
+    /// the matches end. Matches ending at the same place are
+    /// reported longest first.
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let mut state = ROOT;
+        text.bytes().enumerate().flat_map(move |(i, b)| {
+            state = self.next[state][b as usize] as usize;
+            self.outputs[state].iter().map(move |&pattern| Match {
+                pattern,
+                start: i + 1 - self.patterns[pattern].len(),
+                end: i + 1,
+            })
+        })
+    }
+
+    /// Returns the match that starts first and the match that
+    /// starts last in `text`, found in a single pass.
+    pub fn first_and_last(&self, text: &str) -> Option<(Match, Match)> {
+        self.find_iter(text).fold(None, |acc, m| match acc {
+            None => Some((m, m)),
+            Some((first, last)) => Some((
+                if m.start < first.start { m } else { first },
+                if m.start > last.start { m } else { last },
+            )),
+        })
+    }
+}
+
+/// A set of words, each mapped to a value, that can be
+/// found anywhere in some text (eg digit names for day 1).
+#[derive(Debug, Clone)]
+pub struct Vocabulary<T> {
+    matcher: Matcher,
+    values: Vec<T>,
+}
+
+impl<T> Vocabulary<T> {
+    pub fn new<S: AsRef<str>>(entries: impl IntoIterator<Item = (S, T)>) -> Self {
+        let (words, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        Self {
+            matcher: Matcher::new(&words),
+            values,
+        }
+    }
+
+    /// Iterates over every word found in `text` (overlaps
+    /// included) along with its value.
+    pub fn find_iter<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (Match, &'a T)> + 'a {
+        self.matcher
+            .find_iter(text)
+            .map(move |m| (m, &self.values[m.pattern]))
+    }
+
+    /// Returns the values of the first and last words
+    /// found in `text`.
+    pub fn first_and_last(&self, text: &str) -> Option<(&T, &T)> {
+        let (first, last) = self.matcher.first_and_last(text)?;
+        Some((&self.values[first.pattern], &self.values[last.pattern]))
+    }
+}
+
+/// The digits `1`-`9`, spelled as numerals and as English
+/// words (`one`-`nine`).
+pub fn english_digits() -> Vocabulary<u32> {
+    let words = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+    let numerals = (1..=9).map(|d| (d.to_string(), d));
+    let words = words.iter().zip(1..).map(|(w, d)| (w.to_string(), d));
+    Vocabulary::new(numerals.chain(words))
+}
+
+/// The Roman numerals `I`-`IX`.
+pub fn roman_digits() -> Vocabulary<u32> {
+    let numerals = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"];
+    Vocabulary::new(numerals.iter().zip(1..))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn found(m: &Matcher, text: &str) -> Vec<(String, usize)> {
+        m.find_iter(text)
+            .map(|x| (m.patterns()[x.pattern].clone(), x.start))
+            .collect()
+    }
+
+    #[test]
+    fn test_overlapping_matches() {
+        let m = Matcher::new(&["one", "eight", "oneight", "two"]);
+        assert_eq!(
+            found(&m, "xoneightwo"),
+            vec![
+                ("one".to_string(), 1),
+                ("oneight".to_string(), 1),
+                ("eight".to_string(), 3),
+                ("two".to_string(), 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_suffix_patterns() {
+        // Classic example where outputs come through
+        // failure links...
+        let m = Matcher::new(&["he", "she", "his", "hers"]);
+        assert_eq!(
+            found(&m, "ushers"),
+            vec![
+                ("she".to_string(), 1),
+                ("he".to_string(), 2),
+                ("hers".to_string(), 2),
+            ]
+        );
+        assert!(found(&m, "xyz").is_empty());
+        assert!(found(&Matcher::new(&[""]), "abc").is_empty());
+    }
+
+    #[test]
+    fn test_first_and_last() {
+        let digits = english_digits();
+        assert_eq!(digits.first_and_last("two1nine"), Some((&2, &9)));
+        assert_eq!(digits.first_and_last("zoneight234"), Some((&1, &4)));
+        assert_eq!(digits.first_and_last("xtwone"), Some((&2, &1)));
+        assert_eq!(digits.first_and_last("7"), Some((&7, &7)));
+        assert_eq!(digits.first_and_last("abc"), None);
+
+        let roman = roman_digits();
+        let found = roman.find_iter("XIV").map(|(_, v)| *v).collect::<Vec<_>>();
+        assert_eq!(found, vec![1, 4, 5]);
+    }
+}
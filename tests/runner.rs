@@ -0,0 +1,31 @@
+use aoc23::runner::{Runner, Solution, Status};
+use std::path::Path;
+
+/// A runner for the solutions cargo built for these tests.
+fn runner() -> Runner {
+    let bin = Path::new(env!("CARGO_BIN_EXE_day01-part1"));
+    Runner::new(bin.parent().expect("Binaries should be in a directory"))
+}
+
+#[test]
+fn test_answers() {
+    // Day 16 prints more after its answer, so this checks
+    // the runner finds the answer line rather than the
+    // last one...
+    let cases = [
+        (Solution::new(1, 1), "55029"),
+        (Solution::new(5, 1), "650599855"),
+        (Solution::new(16, 1), "6361"),
+    ];
+    let runner = runner();
+    for (solution, answer) in cases {
+        let outcome = runner.run(solution);
+        assert_eq!(outcome.status, Status::Ok, "{}", solution.bin_name());
+        assert_eq!(
+            outcome.answer.as_deref(),
+            Some(answer),
+            "{}",
+            solution.bin_name()
+        );
+    }
+}